@@ -0,0 +1,210 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use glob::Pattern;
+use nucleide::{
+    daku::{Daku, Nucleide, Portal, PortalSet},
+    manifest::Manifest,
+    name::Name,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{read, write, Failure, Outcome};
+
+/// Arguments for `nucleide set`
+#[derive(Args)]
+pub(crate) struct Set {
+    /// `.wasm` or `.daku` file
+    file: PathBuf,
+    /// Manifest (`nucleide.toml` or `.json`) to embed
+    #[arg(short, long)]
+    manifest: Option<PathBuf>,
+    /// Replace the portals (such as `Fetch`)
+    #[arg(long = "portal", value_name = "PORTAL")]
+    portals: Vec<String>,
+    /// Replace the portals with the ones the module imports (such as
+    /// `daku.fetch` for `Fetch`)
+    #[arg(long, conflicts_with = "portals")]
+    infer_portals: bool,
+    /// Override the portal an import requires when inferring portals (such as
+    /// `env.http_get=Fetch`)
+    #[arg(
+        long = "portal-import",
+        value_name = "IMPORT=PORTAL",
+        requires = "infer_portals"
+    )]
+    portal_imports: Vec<String>,
+    /// Replace the searchable tags
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    /// Replace the categories (such as `Office`)
+    #[arg(long = "category", value_name = "CATEGORY")]
+    categories: Vec<String>,
+    /// Set the developer name
+    #[arg(long)]
+    developer: Option<String>,
+    /// Set the module name
+    #[arg(long)]
+    module: Option<String>,
+    /// Where to write the module (defaults to overwriting `file`)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Set {
+    pub(crate) fn run(self) -> Outcome {
+        let mut module = read(&self.file)?;
+
+        if let Some(manifest) = &self.manifest {
+            Manifest::read(manifest)?.embed(&mut module, manifest)?;
+        }
+
+        if self.infer_portals {
+            let portal_imports = portal_imports(&self.portal_imports)?;
+
+            module.infer_portals(|import| {
+                portal_imports
+                    .iter()
+                    .find(|(module, name, _)| {
+                        *module == import.module && *name == import.name
+                    })
+                    .map(|&(_, _, portal)| portal)
+                    .or_else(|| Portal::from_import(import))
+            })?;
+        }
+
+        let mut daku = module.daku()?.map(Daku::into_owned).unwrap_or(Daku {
+            portals: PortalSet::new(),
+            nucleide: None,
+        });
+        let mut changed = false;
+
+        if !self.portals.is_empty() {
+            daku.portals = parse_all::<Portal>(&self.portals, "portal")?
+                .into_iter()
+                .collect();
+            changed = true;
+        }
+
+        if !self.tags.is_empty() {
+            let tags = self.tags.iter().map(|tag| tag.clone().into());
+
+            replace(&mut daku, Nucleide::Tags(tags.collect()));
+            changed = true;
+        }
+
+        if !self.categories.is_empty() {
+            let categories = parse_all(&self.categories, "category")?;
+
+            replace(&mut daku, Nucleide::Categories(categories));
+            changed = true;
+        }
+
+        if let Some(developer) = self.developer {
+            replace(&mut daku, Nucleide::Developer(developer.into()));
+            changed = true;
+        }
+
+        if changed {
+            module.set_daku(&daku)?;
+        }
+
+        if let Some(name) = self.module {
+            let mut names: Vec<Name<'static>> = module
+                .names()?
+                .into_iter()
+                .flatten()
+                .filter(|name| !matches!(name, Name::Module(_)))
+                .map(Name::into_owned)
+                .collect();
+
+            names.insert(0, Name::Module(name.into()));
+            module.set_names(&names)?;
+        }
+
+        write(module, self.output.as_deref().unwrap_or(&self.file))?;
+
+        Ok(true)
+    }
+}
+
+pub(crate) fn strip(
+    file: &Path,
+    patterns: &[String],
+    output: Option<&Path>,
+) -> Outcome {
+    let mut module = read(file)?;
+    let patterns = patterns
+        .iter()
+        .map(|pattern| Pattern::new(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (sections, _) = module.sections_lenient();
+    let names: Vec<String> = sections
+        .map(|section| section.name().to_string())
+        .filter(|name| patterns.iter().any(|pattern| pattern.matches(name)))
+        .collect();
+
+    for name in &names {
+        while module.clear_section(name).is_some() {}
+        println!("- `{name}`");
+    }
+
+    write(module, output.unwrap_or(file))?;
+
+    Ok(true)
+}
+
+/// Parse `module.name=Portal` import mappings (the name is after the last
+/// `.`, since module names can contain one).
+fn portal_imports(
+    mappings: &[String],
+) -> Result<Vec<(&str, &str, Portal)>, Failure> {
+    mappings
+        .iter()
+        .map(|mapping| {
+            let invalid = || {
+                Failure(format!(
+                    "expected `module.name=Portal`, got `{mapping}`"
+                ))
+            };
+            let (import, portal) =
+                mapping.split_once('=').ok_or_else(invalid)?;
+            let (module, name) = import.rsplit_once('.').ok_or_else(invalid)?;
+            let portal = parse_all(&[portal.into()], "portal")?;
+
+            Ok((module, name, portal[0]))
+        })
+        .collect()
+}
+
+/// Parse enum values by name.
+fn parse_all<T: DeserializeOwned>(
+    names: &[String],
+    what: &str,
+) -> Result<Vec<T>, Failure> {
+    names
+        .iter()
+        .map(|name| {
+            serde_json::from_value(name.as_str().into())
+                .map_err(|_| Failure(format!("unknown {what} `{name}`")))
+        })
+        .collect()
+}
+
+/// Replace the nucleide subsection of the same kind, or add it in order.
+fn replace(daku: &mut Daku<'_>, subsection: Nucleide<'static>) {
+    let nucleide = daku.nucleide.get_or_insert_with(Vec::new);
+
+    nucleide.retain(|old| old.id() != subsection.id());
+    nucleide.push(subsection);
+    nucleide.sort_by_key(Nucleide::id);
+}
@@ -0,0 +1,347 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use nucleide::{
+    daku::{Nucleide, Severity},
+    name::Name,
+    producers::ProducerKind,
+    ErrorKind, Section,
+};
+use serde_json::Value;
+
+use crate::{read, Field, Outcome};
+
+pub(crate) fn inspect(file: &Path, json: bool) -> Outcome {
+    let module = read(file)?;
+
+    if json {
+        println!("{}", module.report().to_json());
+
+        return Ok(true);
+    }
+
+    let (sections, diagnostics) = module.sections_lenient();
+
+    for diagnostic in &diagnostics {
+        println!("⚠ {diagnostic}");
+    }
+
+    if !diagnostics.is_empty() {
+        println!();
+    }
+
+    for section in sections {
+        print_section(section);
+    }
+
+    Ok(true)
+}
+
+pub(crate) fn get(file: &Path, field: Field) -> Outcome {
+    let module = read(file)?;
+    let value = match field {
+        Field::Module => {
+            let names = module.names()?.unwrap_or_default();
+            let name = names.into_iter().find_map(|name| match name {
+                Name::Module(name) => Some(name),
+                _ => None,
+            });
+
+            serde_json::to_value(name)?
+        }
+        Field::Producers => serde_json::to_value(module.producers()?)?,
+        Field::Portals => {
+            serde_json::to_value(module.daku()?.map(|daku| daku.portals))?
+        }
+        _ => {
+            let nucleide = module.daku()?.and_then(|daku| daku.nucleide);
+            let subsection = nucleide.into_iter().flatten().find(|sub| {
+                matches!(
+                    (field, sub),
+                    (Field::Name, Nucleide::LocalizedNames(_))
+                        | (
+                            Field::Description,
+                            Nucleide::LocalizedDescriptions(_)
+                        )
+                        | (Field::Icons, Nucleide::ThemedIcons(_))
+                        | (Field::Assets, Nucleide::LocalizedAssets(_))
+                        | (Field::Tags, Nucleide::Tags(_))
+                        | (Field::Categories, Nucleide::Categories(_))
+                        | (Field::Developer, Nucleide::Developer(_))
+                )
+            });
+
+            match subsection {
+                Some(Nucleide::ThemedIcons(icons)) => {
+                    for icon in icons {
+                        println!("{}\t{} bytes", icon.path, icon.data.len());
+                    }
+
+                    return Ok(true);
+                }
+                Some(Nucleide::LocalizedAssets(assets)) => {
+                    let assets: BTreeMap<String, String> = assets
+                        .into_iter()
+                        .map(|(locale, file)| {
+                            let size = file.data.len();
+
+                            (
+                                locale.to_string(),
+                                format!("{}: {size} bytes", file.path),
+                            )
+                        })
+                        .collect();
+
+                    serde_json::to_value(assets)?
+                }
+                // Serialize the variant's contents, without the variant name
+                Some(subsection) => match serde_json::to_value(subsection)? {
+                    Value::Object(object) => {
+                        object.into_iter().next().map(|(_, v)| v).into()
+                    }
+                    value => value,
+                },
+                None => Value::Null,
+            }
+        }
+    };
+
+    print_value(&value);
+
+    Ok(!value.is_null())
+}
+
+pub(crate) fn validate(file: &Path) -> Outcome {
+    let module = read(file)?;
+    let (_, diagnostics) = module.sections_lenient();
+    let mut valid = diagnostics.is_empty();
+
+    for diagnostic in &diagnostics {
+        println!("✗ {diagnostic}");
+    }
+
+    match module.daku() {
+        Ok(Some(daku)) => {
+            for violation in daku.validate() {
+                valid &= violation.severity() < Severity::Error;
+                println!("✗ `daku` section: {violation}");
+            }
+        }
+        Ok(None) => {
+            valid = false;
+            println!("✗ missing required `daku` section");
+        }
+        // Already reported as a diagnostic
+        Err(_) => {}
+    }
+
+    if valid {
+        println!("✓ {}", file.display());
+    }
+
+    Ok(valid)
+}
+
+pub(crate) fn diff(old: &Path, new: &Path) -> Outcome {
+    let changes = read(old)?.diff(&read(new)?)?;
+
+    for change in &changes {
+        println!("{change}");
+    }
+
+    Ok(true)
+}
+
+/// Print strings raw, lists one item per line, maps as tab-separated pairs,
+/// and anything else as JSON.
+fn print_value(value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::String(string) => println!("{string}"),
+        Value::Array(items) if items.iter().all(Value::is_string) => {
+            for item in items {
+                print_value(item);
+            }
+        }
+        Value::Object(map) if map.values().all(Value::is_string) => {
+            for (key, value) in map {
+                println!("{key}\t{}", value.as_str().unwrap_or_default());
+            }
+        }
+        value => {
+            println!("{}", serde_json::to_string_pretty(value).unwrap());
+        }
+    }
+}
+
+fn print_section(section: Section<'_>) {
+    let name = section.name();
+
+    // Try to downcast section from bytes to type-safe representation.
+    let section = match section.to() {
+        Ok(section) => section,
+        Err(_) if name.starts_with(".debug_") => {
+            println!("§ {name:?} — Skipping DWARF Debug Data");
+            println!();
+            return;
+        }
+        Err(e) if e.kind() == ErrorKind::Unsupported => {
+            println!("§ {name:?} — Didn't know how to parse section");
+            println!();
+            return;
+        }
+        Err(e) => {
+            println!("§ {name:?} — Malformed section: {e}");
+            println!();
+            return;
+        }
+    };
+
+    match section {
+        Section::Name(names) => {
+            println!("§ `name`");
+            println!("————————");
+
+            for name in names {
+                let (kind, names) = match name {
+                    Name::Module(name) => {
+                        println!(" • Module {name:?}");
+                        continue;
+                    }
+                    Name::Local(names) => {
+                        print_indirect("Local", names);
+                        continue;
+                    }
+                    Name::Label(names) => {
+                        print_indirect("Label", names);
+                        continue;
+                    }
+                    Name::Unknown { id, data } => {
+                        println!(
+                            " • Unknown Subsection {id}: {} bytes",
+                            data.len(),
+                        );
+                        continue;
+                    }
+                    Name::Function(names) => ("Function", names),
+                    Name::Global(names) => ("Global", names),
+                    Name::Data(names) => ("Data", names),
+                    Name::Type(names) => ("Types", names),
+                    Name::Table(names) => ("Table", names),
+                    Name::Memory(names) => ("Memory", names),
+                    Name::Element(names) => ("Element", names),
+                };
+
+                println!(" • {kind}");
+
+                for (id, name) in names {
+                    println!("   {id}. {name:?}");
+                }
+            }
+
+            println!();
+        }
+        Section::Producers(producers) => {
+            println!("§ `producers`");
+            println!("—————————————");
+
+            for producer_group in producers {
+                let kind = match &producer_group.kind {
+                    ProducerKind::Language => "Language",
+                    ProducerKind::ProcessedBy => "Processed By",
+                    ProducerKind::Sdk => "SDK",
+                    ProducerKind::Other(kind) => kind,
+                };
+
+                println!(" • {kind}:");
+
+                for producer in producer_group.list {
+                    let name = producer.name;
+                    let version = producer.version;
+
+                    println!("   • Name: {name:?}, Version: {version:?}")
+                }
+            }
+
+            println!();
+        }
+        Section::Daku(daku) => {
+            println!("§ `daku`");
+            println!("————————");
+            println!(" • Portals:");
+
+            for portal in &daku.portals {
+                println!("   • {portal:?}");
+            }
+
+            if let Some(nucleide) = daku.nucleide {
+                println!(" • Nucleide Extension:");
+
+                for subsection in nucleide {
+                    print_nucleide(subsection);
+                }
+            }
+
+            println!();
+        }
+        Section::Any { .. } => unreachable!(),
+    }
+}
+
+fn print_indirect(
+    kind: &str,
+    names: BTreeMap<u32, BTreeMap<u32, Cow<'_, str>>>,
+) {
+    println!(" • {kind}");
+
+    for (id, names) in names {
+        println!("   {id}. {names:?}");
+    }
+}
+
+fn print_nucleide(subsection: Nucleide<'_>) {
+    match subsection {
+        Nucleide::LocalizedNames(locale_name_map) => {
+            println!("   • Localized Names: {locale_name_map:?}");
+        }
+        Nucleide::LocalizedDescriptions(locale_name_map) => {
+            println!("   • Localized Descriptions: {locale_name_map:?}");
+        }
+        Nucleide::ThemedIcons(file_list) => {
+            println!("   • Themed Icons:");
+
+            for file in file_list {
+                println!("     • {}: {} bytes", file.path, file.data.len());
+            }
+        }
+        Nucleide::LocalizedAssets(locale_file_map) => {
+            println!("   • Localized Assets:");
+
+            for (locale, file) in locale_file_map {
+                let size = file.data.len();
+
+                println!("     • {locale}: {}: {size} bytes", file.path);
+            }
+        }
+        Nucleide::Tags(tags) => {
+            println!("   • Tags: {tags:?}");
+        }
+        Nucleide::Categories(categories) => {
+            println!("   • Categories: {categories:?}");
+        }
+        Nucleide::Developer(developer) => {
+            println!("   • Developer: {developer:?}");
+        }
+        Nucleide::Unknown { id, data } => {
+            println!("   • Unknown Subsection {id}: {} bytes", data.len());
+        }
+    }
+}
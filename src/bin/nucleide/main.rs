@@ -0,0 +1,177 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Command-line tool to view and edit WebAssembly app metadata.
+
+mod edit;
+mod inspect;
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use nucleide::{manifest::Manifest, Module};
+
+/// View and edit the metadata of WebAssembly apps
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print every custom section of a module
+    Inspect {
+        /// `.wasm` or `.daku` file
+        file: PathBuf,
+        /// Print JSON instead of human-readable text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print one metadata field
+    Get {
+        /// `.wasm` or `.daku` file
+        file: PathBuf,
+        /// Field to print
+        field: Field,
+    },
+    /// Set metadata from a manifest or flags
+    Set(edit::Set),
+    /// Remove custom sections
+    Strip {
+        /// `.wasm` or `.daku` file
+        file: PathBuf,
+        /// Section names or glob patterns (such as `.debug_*`)
+        #[arg(required = true)]
+        patterns: Vec<String>,
+        /// Where to write the module (defaults to overwriting `file`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write icons and assets out to a directory
+    ExtractAssets {
+        /// `.wasm` or `.daku` file
+        file: PathBuf,
+        /// Directory to write files into
+        dir: PathBuf,
+        /// Also write a `nucleide.toml` manifest into the directory
+        #[arg(long)]
+        manifest: bool,
+    },
+    /// Check that metadata is well-formed and follows the specification
+    /// (exits non-zero if not)
+    Validate {
+        /// `.wasm` or `.daku` file
+        file: PathBuf,
+    },
+    /// Show what metadata changed between two modules
+    Diff {
+        /// Original `.wasm` or `.daku` file
+        old: PathBuf,
+        /// Changed `.wasm` or `.daku` file
+        new: PathBuf,
+    },
+}
+
+/// Metadata field
+#[derive(Clone, Copy, ValueEnum)]
+enum Field {
+    Portals,
+    Name,
+    Description,
+    Icons,
+    Assets,
+    Tags,
+    Categories,
+    Developer,
+    Module,
+    Producers,
+}
+
+/// Command failure
+struct Failure(String);
+
+impl<E: fmt::Display> From<E> for Failure {
+    fn from(error: E) -> Self {
+        Self(error.to_string())
+    }
+}
+
+/// Command outcome: `Ok(false)` if the command ran, but found problems
+type Outcome = Result<bool, Failure>;
+
+fn main() -> ExitCode {
+    let outcome = match Cli::parse().command {
+        Command::Inspect { file, json } => inspect::inspect(&file, json),
+        Command::Get { file, field } => inspect::get(&file, field),
+        Command::Set(set) => set.run(),
+        Command::Strip {
+            file,
+            patterns,
+            output,
+        } => edit::strip(&file, &patterns, output.as_deref()),
+        Command::ExtractAssets {
+            file,
+            dir,
+            manifest,
+        } => extract_assets(&file, &dir, manifest),
+        Command::Validate { file } => inspect::validate(&file),
+        Command::Diff { old, new } => inspect::diff(&old, &new),
+    };
+
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(Failure(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Read a `.wasm` or `.daku` file.
+fn read(path: &Path) -> Result<Module, Failure> {
+    let bytes = fs::read(path)
+        .map_err(|e| Failure(format!("{}: {e}", path.display())))?;
+
+    Module::new(&bytes).map_err(|e| Failure(format!("{}: {e}", path.display())))
+}
+
+/// Write a module, compressed if the file extension is `.daku`.
+fn write(module: Module, path: &Path) -> Result<(), Failure> {
+    let daku = path.extension().is_some_and(|ext| ext == "daku");
+    let bytes = if daku {
+        module.into_daku(0)?
+    } else {
+        module.into_buffer()?
+    };
+
+    fs::write(path, bytes)
+        .map_err(|e| Failure(format!("{}: {e}", path.display())))
+}
+
+fn extract_assets(file: &Path, dir: &Path, manifest: bool) -> Outcome {
+    let module = read(file)?;
+
+    fs::create_dir_all(dir)
+        .map_err(|e| Failure(format!("{}: {e}", dir.display())))?;
+
+    let exported = Manifest::export(&module, dir)?;
+
+    if manifest {
+        exported.write(dir.join("nucleide.toml"))?;
+    }
+
+    Ok(true)
+}
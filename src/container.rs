@@ -0,0 +1,309 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::{string::String, vec::Vec};
+use std::io::{self, Cursor, Read};
+
+use crate::{
+    module::HEADER, parse::Reader, wasm::Read as _, Error, ErrorKind, Module,
+    Result, Section,
+};
+
+/// Magic number at the start of every zstd frame
+pub(crate) const MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Largest decompressed module (1 GiB), so that a small `.daku` file can't
+/// use up all of the memory
+pub(crate) const MAX_SIZE: usize = 1 << 30;
+
+/// Decompress a `.daku` file.
+pub(crate) fn decompress(buf: &[u8]) -> Result<Vec<u8>> {
+    let io = |_| Error::new(ErrorKind::Io, 0);
+    let decoder = zstd::Decoder::new(buf).map_err(io)?;
+    let mut module = Vec::new();
+
+    decoder
+        .take(MAX_SIZE as u64 + 1)
+        .read_to_end(&mut module)
+        .map_err(io)?;
+
+    if module.len() > MAX_SIZE {
+        return Err(Error::new(ErrorKind::TooLarge, MAX_SIZE));
+    }
+
+    Ok(module)
+}
+
+impl Module {
+    /// Write out module as a zstd-compressed `.daku` file.
+    ///
+    /// `level` is the zstd compression level (1-22); `0` uses zstd's default.
+    pub fn into_daku(self, level: i32) -> Result<Vec<u8>> {
+        let buffer = self.into_buffer()?;
+
+        zstd::encode_all(buffer.as_slice(), level)
+            .map_err(|_| Error::new(ErrorKind::Io, 0))
+    }
+
+    /// Read only the custom sections called one of `names` from a WebAssembly
+    /// module or `.daku` file.
+    ///
+    /// Stops reading (and decompressing) as soon as all of the requested
+    /// sections have been found, and non-custom sections are skipped over
+    /// without being kept in memory.  Sections are returned in the order they
+    /// appear in the module (only the first of each name).
+    ///
+    /// Offsets in errors are relative to the start of the decompressed module.
+    /// Fails with [`ErrorKind::TooLarge`] if a requested section would extend
+    /// past the first GiB of the module.
+    pub fn read_sections(
+        reader: impl Read,
+        names: &[&str],
+    ) -> Result<Vec<Section<'static>>> {
+        let mut reader = reader;
+        let mut magic = [0; 4];
+
+        read_exact(&mut reader, &mut magic, 0)?;
+
+        let magic = Cursor::new(magic);
+
+        if magic.get_ref() == &MAGIC {
+            let decoder = zstd::Decoder::new(magic.chain(reader))
+                .map_err(|_| Error::new(ErrorKind::Io, 0))?;
+
+            Scanner::new(decoder).sections(names)
+        } else {
+            Scanner::new(magic.chain(reader)).sections(names)
+        }
+    }
+}
+
+/// Streaming section scanner
+struct Scanner<R: Read> {
+    reader: R,
+    offset: usize,
+}
+
+impl<R: Read> Scanner<R> {
+    fn new(reader: R) -> Self {
+        Self { reader, offset: 0 }
+    }
+
+    fn sections(mut self, names: &[&str]) -> Result<Vec<Section<'static>>> {
+        let mut found: Vec<Section<'static>> = Vec::new();
+        let mut header = [0; HEADER.len()];
+
+        self.bytes(&mut header)?;
+
+        if header != HEADER {
+            return Err(Error::new(ErrorKind::InvalidHeader, 0));
+        }
+
+        while found.len() < names.len() {
+            let mut id = [0];
+
+            // End of module
+            if self.reader.read(&mut id).map_err(|_| self.io())? == 0 {
+                break;
+            }
+
+            self.offset += 1;
+
+            let size = self.integer()?;
+
+            if id[0] != 0 {
+                self.skip(size)?;
+                continue;
+            }
+
+            let offset = self.offset;
+
+            if offset.saturating_add(size) > MAX_SIZE {
+                return Err(Error::new(ErrorKind::TooLarge, MAX_SIZE));
+            }
+
+            let contents = self.read(size)?;
+
+            let mut reader = Reader::with_offset(&contents, offset);
+            let name = reader.name()?;
+            let wanted = names.contains(&&*name)
+                && !found.iter().any(|section| section.name() == name);
+
+            if wanted {
+                let name = String::from(name);
+                let data = contents[reader.offset() - offset..].to_vec();
+
+                found.push(Section::Any {
+                    name: name.into(),
+                    data: data.into(),
+                });
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Read a ULEB128-encoded 32-bit length.
+    fn integer(&mut self) -> Result<usize> {
+        let mut bytes = Vec::new();
+
+        loop {
+            let mut byte = [0];
+
+            self.bytes(&mut byte)?;
+            bytes.push(byte[0]);
+
+            if byte[0] & 0x80 == 0 || bytes.len() == 5 {
+                break;
+            }
+        }
+
+        let offset = self.offset - bytes.len();
+
+        Reader::with_offset(&bytes, offset).length()
+    }
+
+    /// Fill `buf`.
+    fn bytes(&mut self, buf: &mut [u8]) -> Result {
+        read_exact(&mut self.reader, buf, self.offset)?;
+        self.offset += buf.len();
+
+        Ok(())
+    }
+
+    /// Read `len` bytes, allocating only as much as is actually read.
+    fn read(&mut self, len: usize) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        let read = (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut buf)
+            .map_err(|_| self.io())?;
+
+        if read != len {
+            return Err(Error::new(ErrorKind::Truncated, self.offset + read));
+        }
+
+        self.offset += len;
+
+        Ok(buf)
+    }
+
+    /// Skip `len` bytes.
+    fn skip(&mut self, len: usize) -> Result {
+        let len64 = len.try_into().unwrap_or(u64::MAX);
+        let copied =
+            io::copy(&mut (&mut self.reader).take(len64), &mut io::sink())
+                .map_err(|_| self.io())?;
+
+        if copied != len64 {
+            return Err(Error::new(
+                ErrorKind::Truncated,
+                self.offset + usize::try_from(copied).unwrap_or(len),
+            ));
+        }
+
+        self.offset += len;
+
+        Ok(())
+    }
+
+    fn io(&self) -> Error {
+        Error::new(ErrorKind::Io, self.offset)
+    }
+}
+
+/// Fill `buf` from `reader`, which is at `offset`.
+fn read_exact(reader: &mut impl Read, buf: &mut [u8], offset: usize) -> Result {
+    reader.read_exact(buf).map_err(|e| {
+        let kind = match e.kind() {
+            io::ErrorKind::UnexpectedEof => ErrorKind::Truncated,
+            _ => ErrorKind::Io,
+        };
+
+        Error::new(kind, offset)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daku::{Daku, PortalSet};
+
+    #[test]
+    fn daku_container() {
+        let mut module = Module::new(&HEADER).unwrap();
+        let daku = Daku {
+            portals: PortalSet::new(),
+            nucleide: None,
+        };
+
+        module
+            .set_section(Section::Any {
+                name: "a".into(),
+                data: [1, 2, 3][..].into(),
+            })
+            .unwrap();
+        module.set_daku(&daku).unwrap();
+        module
+            .set_section(Section::Any {
+                name: "b".into(),
+                data: [4][..].into(),
+            })
+            .unwrap();
+
+        let wasm = Module::new(&HEADER).unwrap().into_buffer().unwrap();
+        let compressed = module.into_daku(19).unwrap();
+
+        assert_eq!(compressed[..4], MAGIC);
+
+        let module = Module::new(&compressed).unwrap();
+
+        assert_eq!(module.daku().unwrap().unwrap(), daku);
+
+        let sections =
+            Module::read_sections(compressed.as_slice(), &["b", "daku", "c"])
+                .unwrap();
+
+        assert_eq!(
+            sections,
+            [
+                Section::Any {
+                    name: "daku".into(),
+                    data: [0][..].into(),
+                },
+                Section::Any {
+                    name: "b".into(),
+                    data: [4][..].into(),
+                },
+            ],
+        );
+        assert!(Module::read_sections(wasm.as_slice(), &["daku"])
+            .unwrap()
+            .is_empty());
+
+        let error =
+            Module::read_sections(&compressed[..8], &["daku"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Truncated);
+
+        // Custom section claiming to be 4 GiB long
+        let huge = [&HEADER[..], &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x01]];
+        let error = Module::read_sections(huge.concat().as_slice(), &["a"])
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::TooLarge);
+
+        let short = [&HEADER[..], &[0x00, 0x05, 0x01, b'a']];
+        let error = Module::read_sections(short.concat().as_slice(), &["a"])
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Truncated);
+        assert_eq!(error.offset(), HEADER.len() + 4);
+    }
+}
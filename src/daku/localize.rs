@@ -0,0 +1,184 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::collections::BTreeMap;
+
+use crate::{
+    daku::{Daku, File, Nucleide},
+    locale::Locale,
+};
+
+/// Locale tried after all of the preferred locales
+pub(super) const FALLBACK: Locale = Locale::Code {
+    language: *b"en",
+    region: *b"US",
+};
+
+/// A localized value, along with the locale that was chosen for it
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Localized<T> {
+    /// Locale of the value ([`Locale::Any`] if not localized)
+    pub locale: Locale,
+    /// The value
+    pub value: T,
+}
+
+impl<'a> Daku<'a> {
+    /// Pick the best localized name for a user's `preferred` locales (most
+    /// preferred first).
+    ///
+    /// Each preferred locale is tried exactly, and then any locale with the
+    /// same language.  If none match, `enUS` is tried, and then the
+    /// non-localized name.
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use nucleide::{daku::{Daku, Nucleide, PortalSet}, Locale};
+    ///
+    /// let daku = Daku {
+    ///     portals: PortalSet::new(),
+    ///     nucleide: Some(vec![Nucleide::LocalizedNames(BTreeMap::from([
+    ///         ("enUS".parse().unwrap(), "Notes".into()),
+    ///         ("frCA".parse().unwrap(), "Bloc-notes".into()),
+    ///     ]))]),
+    /// };
+    /// let fr_fr: Locale = "fr-FR".parse().unwrap();
+    /// let de_de: Locale = "de-DE".parse().unwrap();
+    ///
+    /// assert_eq!(daku.name(&[fr_fr]).unwrap().value, "Bloc-notes");
+    /// assert_eq!(daku.name(&[fr_fr]).unwrap().locale.to_string(), "frCA");
+    /// assert_eq!(daku.name(&[de_de]).unwrap().value, "Notes");
+    /// ```
+    pub fn name(&self, preferred: &[Locale]) -> Option<Localized<&str>> {
+        self.nucleide.iter().flatten().find_map(|subsection| {
+            let Nucleide::LocalizedNames(names) = subsection else {
+                return None;
+            };
+
+            negotiate(names, preferred).map(|(locale, name)| Localized {
+                locale,
+                value: &**name,
+            })
+        })
+    }
+
+    /// Pick the best localized description for a user's `preferred` locales,
+    /// the same way as [`Daku::name()`].
+    pub fn description(&self, preferred: &[Locale]) -> Option<Localized<&str>> {
+        self.nucleide.iter().flatten().find_map(|subsection| {
+            let Nucleide::LocalizedDescriptions(descriptions) = subsection
+            else {
+                return None;
+            };
+
+            negotiate(descriptions, preferred).map(|(locale, description)| {
+                Localized {
+                    locale,
+                    value: &**description,
+                }
+            })
+        })
+    }
+
+    /// Pick the best localized asset for a user's `preferred` locales, the
+    /// same way as [`Daku::name()`].
+    pub fn asset(&self, preferred: &[Locale]) -> Option<Localized<&File<'a>>> {
+        self.nucleide.iter().flatten().find_map(|subsection| {
+            let Nucleide::LocalizedAssets(assets) = subsection else {
+                return None;
+            };
+
+            negotiate(assets, preferred)
+                .map(|(locale, value)| Localized { locale, value })
+        })
+    }
+}
+
+/// Choose an entry from a localized map for the `preferred` locales.
+pub(super) fn negotiate<'m, V>(
+    map: &'m BTreeMap<Locale, V>,
+    preferred: &[Locale],
+) -> Option<(Locale, &'m V)> {
+    let get = |locale: &Locale| {
+        map.get_key_value(locale)
+            .map(|(&locale, value)| (locale, value))
+    };
+
+    preferred
+        .iter()
+        .enumerate()
+        .find_map(|(i, locale)| {
+            get(locale).or_else(|| {
+                let language = locale.language()?;
+                let same_language =
+                    |locale: &Locale| locale.language() == Some(language);
+
+                // Prefer a more specific locale the user also listed
+                preferred[i + 1..]
+                    .iter()
+                    .filter(|locale| same_language(locale))
+                    .find_map(get)
+                    .or_else(|| {
+                        map.iter()
+                            .find(|(locale, _)| same_language(locale))
+                            .map(|(&locale, value)| (locale, value))
+                    })
+            })
+        })
+        .or_else(|| get(&FALLBACK))
+        .or_else(|| get(&Locale::Any))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::daku::PortalSet;
+
+    #[test]
+    fn negotiate() {
+        let [fr_fr, fr_be, fr_ca, en_us, de_de] =
+            ["frFR", "frBE", "frCA", "enUS", "deDE"]
+                .map(|locale| locale.parse::<Locale>().unwrap());
+        let names = BTreeMap::from([
+            (Locale::Any, "notes"),
+            (fr_be, "Notes (BE)"),
+            (fr_ca, "Notes (CA)"),
+            (de_de, "Notizen"),
+        ]);
+        let pick = |preferred: &[Locale]| {
+            super::negotiate(&names, preferred).map(|(locale, _)| locale)
+        };
+
+        assert_eq!(pick(&[fr_fr]), Some(fr_ca));
+        assert_eq!(pick(&[fr_fr, fr_be]), Some(fr_be));
+        assert_eq!(pick(&[en_us, de_de]), Some(de_de));
+        assert_eq!(pick(&[en_us]), Some(Locale::Any));
+        assert_eq!(pick(&[]), Some(Locale::Any));
+
+        let daku = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::LocalizedNames(BTreeMap::from([(
+                en_us,
+                "Notes".into(),
+            )]))]),
+        };
+
+        assert_eq!(
+            daku.name(&[de_de]),
+            Some(Localized {
+                locale: en_us,
+                value: "Notes",
+            }),
+        );
+        assert_eq!(daku.description(&[de_de]), None);
+    }
+}
@@ -11,16 +11,28 @@
 //!
 //! [daku]: https://ardaku.org/daku/
 
+mod localize;
 mod nucleide;
 mod portal;
+mod portal_set;
 mod read;
 mod section;
+mod translate;
+mod usage;
+mod validate;
+mod version;
 mod write;
 
 pub use self::{
+    localize::Localized,
     nucleide::{Category, File, Nucleide},
-    portal::Portal,
+    portal::{Portal, PortalGroup, Risk},
+    portal_set::{PortalSet, PortalSetIter},
     read::Read,
     section::Daku,
+    translate::{English, Translation, Translations},
+    usage::PortalUsage,
+    validate::{Severity, Violation},
+    version::SpecVersion,
     write::Write,
 };
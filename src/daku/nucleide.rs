@@ -9,11 +9,17 @@
 
 use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
 
-use num_enum::{IntoPrimitive as Into, TryFromPrimitive as TryFrom};
+use num_enum::{FromPrimitive, IntoPrimitive as Into};
+
+use crate::{locale::Locale, name::owned_map};
 
 /// App category (Nucleide extension)
+///
+/// Categories this crate doesn't recognize are kept as [`Category::Unknown`],
+/// so they round-trip unchanged.
 #[repr(u32)]
-#[derive(Debug, Copy, Clone, Into, TryFrom)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Into, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Category {
     /// Applications for playing / recording / editing audio, video, drawing,
     /// photos, fonts, 3D-modeling
@@ -41,32 +47,123 @@ pub enum Category {
     Life = 0x08,
     /// Applications for coupons, buying/selling, trading, currency
     Finance = 0x09,
+    /// Category that isn't recognized, by ID
+    ///
+    /// This must not hold the ID of a recognized category: `Unknown(3)`
+    /// isn't equal to [`Category::Coding`], but would be read back as it, so
+    /// writing it is an error.  Use [`Category::from()`] to get the category
+    /// for an ID.
+    #[num_enum(catch_all)]
+    Unknown(u32),
+}
+
+impl Category {
+    /// Returns true if the category is one this crate recognizes.
+    pub fn is_recognized(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
 }
 
 /// Metadata file (Nucleide extension)
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct File<'a> {
     /// The path of the file
     pub path: Cow<'a, str>,
     /// Data in the file
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialize::base64"))]
     pub data: Cow<'a, [u8]>,
 }
 
 /// Nucleide subsection extension for Daku (for use with Nucleic desktop)
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Nucleide<'a> {
     /// Localized names for the Nucleic desktop application
-    LocalizedNames(BTreeMap<u32, Cow<'a, str>>),
+    LocalizedNames(BTreeMap<Locale, Cow<'a, str>>),
     /// Localized descriptions for the app Emporium
-    LocalizedDescriptions(BTreeMap<u32, Cow<'a, str>>),
+    LocalizedDescriptions(BTreeMap<Locale, Cow<'a, str>>),
     /// Icons for each theme (standard ones are "default" and "reduced")
     ThemedIcons(Vec<File<'a>>),
     /// Localized assets for the app Emporium
-    LocalizedAssets(BTreeMap<u32, File<'a>>),
+    LocalizedAssets(BTreeMap<Locale, File<'a>>),
     /// English lowercase words separated by spaces (no punctuation allowed)
     Tags(Vec<Cow<'a, str>>),
     /// App category for the Emporium (limit 2)
     Categories(Vec<Category>),
     /// Name of organization/company/developer of application
     Developer(Cow<'a, str>),
+    /// Subsection from a newer version of the specification, kept as-is
+    Unknown {
+        /// Subsection ID (as numbered in [`SpecVersion::V1`](crate::daku::SpecVersion::V1))
+        id: u8,
+        /// Raw contents of the subsection
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serialize::base64")
+        )]
+        data: Cow<'a, [u8]>,
+    },
+}
+
+impl File<'_> {
+    /// Convert into a file that owns all of its data.
+    pub fn into_owned(self) -> File<'static> {
+        File {
+            path: self.path.into_owned().into(),
+            data: self.data.into_owned().into(),
+        }
+    }
+}
+
+impl Nucleide<'_> {
+    /// Get the subsection ID, as numbered in
+    /// [`SpecVersion::V1`](crate::daku::SpecVersion::V1) (which is also the
+    /// order subsections are written in).
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::LocalizedNames(_) => 1,
+            Self::LocalizedDescriptions(_) => 2,
+            Self::ThemedIcons(_) => 3,
+            Self::LocalizedAssets(_) => 4,
+            Self::Tags(_) => 5,
+            Self::Categories(_) => 6,
+            Self::Developer(_) => 7,
+            Self::Unknown { id, .. } => *id,
+        }
+    }
+
+    /// Convert into a nucleide subsection that owns all of its data.
+    pub fn into_owned(self) -> Nucleide<'static> {
+        match self {
+            Self::LocalizedNames(map) => {
+                Nucleide::LocalizedNames(owned_map(map))
+            }
+            Self::LocalizedDescriptions(map) => {
+                Nucleide::LocalizedDescriptions(owned_map(map))
+            }
+            Self::ThemedIcons(files) => Nucleide::ThemedIcons(
+                files.into_iter().map(File::into_owned).collect(),
+            ),
+            Self::LocalizedAssets(files) => Nucleide::LocalizedAssets(
+                files
+                    .into_iter()
+                    .map(|(locale, file)| (locale, file.into_owned()))
+                    .collect(),
+            ),
+            Self::Tags(tags) => Nucleide::Tags(
+                tags.into_iter()
+                    .map(|tag| tag.into_owned().into())
+                    .collect(),
+            ),
+            Self::Categories(categories) => Nucleide::Categories(categories),
+            Self::Developer(developer) => {
+                Nucleide::Developer(developer.into_owned().into())
+            }
+            Self::Unknown { id, data } => Nucleide::Unknown {
+                id,
+                data: data.into_owned().into(),
+            },
+        }
+    }
 }
@@ -7,31 +7,35 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use num_enum::{IntoPrimitive as Into, TryFromPrimitive as TryFrom};
+use num_enum::{FromPrimitive, IntoPrimitive as Into};
 
 /// A portal
+///
+/// Portals this crate doesn't recognize (from a newer version of the Daku
+/// runtime) are kept as [`Portal::Unknown`], so they round-trip unchanged.
 #[repr(u32)]
-#[derive(Debug, Copy, Clone, Into, TryFrom)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Into, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Portal {
     /// Logging API (stdout/printf)
     Log = 0x00,
     /// Developer command API (stdin/scanf)
     Prompt = 0x01,
-    ///
+    /// Network client API (connect to servers, such as with HTTP)
     Fetch = 0x02,
-    ///
+    /// Network server API (accept connections from other devices)
     Serve = 0x03,
-    ///
+    /// Audio playback API
     Speakers = 0x04,
-    ///
+    /// Audio recording API (microphone)
     Microphone = 0x05,
-    ///
+    /// Screen recording API
     Screen = 0x06,
-    ///
+    /// Camera capture API (pictures and video)
     Camera = 0x07,
-    ///
+    /// Windowing API (open windows and draw in them)
     Window = 0x08,
-    ///
+    /// Task spawning API (launch other apps and background tasks)
     Spawn = 0x09,
     /// Set user information API (username, display name, localization)
     User = 0x0A,
@@ -41,16 +45,124 @@ pub enum Portal {
     System = 0x0C,
     /// Get system information and settings
     About = 0x0D,
-    ///
+    /// Filesystem API (read and write files)
     File = 0x0E,
-    ///
+    /// Human interface device API (keyboard, mouse, gamepads, etc.)
     Hid = 0x0F,
-    ///
+    /// Timer API (wait, and run tasks at intervals)
     Timer = 0x10,
-    ///
+    /// Clock API (current date and time)
     Clock = 0x11,
-    ///
+    /// GPU API (graphics and compute)
     Gpu = 0x12,
-    ///
+    /// Geolocation API (where the device is)
     Location = 0x13,
+    /// Portal that isn't recognized, by ID
+    ///
+    /// This shouldn't hold the ID of a recognized portal: `Unknown(2)` isn't
+    /// equal to [`Portal::Fetch`].  Use [`Portal::from()`] to get the portal
+    /// for an ID.  A [`PortalSet`](crate::daku::PortalSet) stores portals by
+    /// ID, so adding `Unknown(2)` to one adds `Fetch`.
+    #[num_enum(catch_all)]
+    Unknown(u32),
+}
+
+/// How much of a user's privacy or security a [`Portal`] can put at risk
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Risk {
+    /// Can't reach anything outside of the app
+    Low,
+    /// Can reach the network, files or other apps
+    Moderate,
+    /// Can record the user, find where they are, or change their account or
+    /// system
+    Sensitive,
+}
+
+/// Group of related portals, for asking the user for permission once
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PortalGroup {
+    /// [`Portal::Log`] and [`Portal::Prompt`]
+    Console,
+    /// [`Portal::Fetch`] and [`Portal::Serve`]
+    Network,
+    /// [`Portal::Speakers`], [`Portal::Window`] and [`Portal::Gpu`]
+    Output,
+    /// [`Portal::Microphone`], [`Portal::Screen`] and [`Portal::Camera`]
+    MediaCapture,
+    /// [`Portal::Spawn`]
+    Apps,
+    /// [`Portal::User`] and [`Portal::Preferences`]
+    Account,
+    /// [`Portal::System`] and [`Portal::About`]
+    System,
+    /// [`Portal::File`]
+    Files,
+    /// [`Portal::Hid`]
+    Input,
+    /// [`Portal::Timer`] and [`Portal::Clock`]
+    Time,
+    /// [`Portal::Location`]
+    Location,
+    /// Portals that aren't recognized
+    Unknown,
+}
+
+impl Portal {
+    /// Returns true if the portal is one this crate recognizes.
+    pub fn is_recognized(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+
+    /// Get how much of the user's privacy or security the portal can put at
+    /// risk.
+    ///
+    /// Unrecognized portals are assumed to be [`Risk::Sensitive`].
+    pub fn risk(&self) -> Risk {
+        match self {
+            Self::Log
+            | Self::Prompt
+            | Self::Speakers
+            | Self::Window
+            | Self::About
+            | Self::Timer
+            | Self::Clock
+            | Self::Gpu => Risk::Low,
+            Self::Fetch
+            | Self::Serve
+            | Self::Spawn
+            | Self::Preferences
+            | Self::File
+            | Self::Hid => Risk::Moderate,
+            Self::Microphone
+            | Self::Screen
+            | Self::Camera
+            | Self::User
+            | Self::System
+            | Self::Location
+            | Self::Unknown(_) => Risk::Sensitive,
+        }
+    }
+
+    /// Get the group of related portals this portal belongs to.
+    pub fn group(&self) -> PortalGroup {
+        match self {
+            Self::Log | Self::Prompt => PortalGroup::Console,
+            Self::Fetch | Self::Serve => PortalGroup::Network,
+            Self::Speakers | Self::Window | Self::Gpu => PortalGroup::Output,
+            Self::Microphone | Self::Screen | Self::Camera => {
+                PortalGroup::MediaCapture
+            }
+            Self::Spawn => PortalGroup::Apps,
+            Self::User | Self::Preferences => PortalGroup::Account,
+            Self::System | Self::About => PortalGroup::System,
+            Self::File => PortalGroup::Files,
+            Self::Hid => PortalGroup::Input,
+            Self::Timer | Self::Clock => PortalGroup::Time,
+            Self::Location => PortalGroup::Location,
+            Self::Unknown(_) => PortalGroup::Unknown,
+        }
+    }
 }
@@ -0,0 +1,281 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::collections::{btree_set, BTreeSet};
+use core::fmt;
+
+use crate::daku::Portal;
+
+/// A set of portals, ordered by ID
+///
+/// Portals with IDs below 64 are stored as a bitmask (see
+/// [`PortalSet::to_bits()`]), and any others separately.
+///
+/// ```rust
+/// use nucleide::daku::{Portal, PortalSet};
+///
+/// let v1 = PortalSet::from([Portal::Timer, Portal::Log]);
+/// let v2 = PortalSet::from([Portal::Fetch, Portal::Log, Portal::Timer]);
+///
+/// assert!(v2.contains(Portal::Fetch));
+/// assert_eq!(v2.difference(&v1), PortalSet::from([Portal::Fetch]));
+/// assert_eq!(
+///     v2.iter().collect::<Vec<_>>(),
+///     [Portal::Log, Portal::Fetch, Portal::Timer],
+/// );
+/// assert_eq!(v1.to_bits(), Some(1 << 0x00 | 1 << 0x10));
+/// ```
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct PortalSet {
+    /// Portals with IDs below 64
+    bits: u64,
+    /// IDs of the portals that don't fit in `bits`
+    extra: BTreeSet<u32>,
+}
+
+impl PortalSet {
+    /// Create an empty portal set.
+    pub const fn new() -> Self {
+        Self {
+            bits: 0,
+            extra: BTreeSet::new(),
+        }
+    }
+
+    /// Create a portal set from a bitmask, where bit `n` is the portal with
+    /// ID `n`.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self {
+            bits,
+            extra: BTreeSet::new(),
+        }
+    }
+
+    /// Get the bitmask of the set (as in [`PortalSet::from_bits()`]).
+    ///
+    /// Returns `None` if the set contains a portal with an ID of 64 or more.
+    pub fn to_bits(&self) -> Option<u64> {
+        self.extra.is_empty().then_some(self.bits)
+    }
+
+    /// Add a portal to the set, returning true if it wasn't already in it.
+    pub fn insert(&mut self, portal: Portal) -> bool {
+        match bit(portal) {
+            Ok(bit) => {
+                let inserted = self.bits & bit == 0;
+
+                self.bits |= bit;
+                inserted
+            }
+            Err(id) => self.extra.insert(id),
+        }
+    }
+
+    /// Remove a portal from the set, returning true if it was in it.
+    pub fn remove(&mut self, portal: Portal) -> bool {
+        match bit(portal) {
+            Ok(bit) => {
+                let removed = self.bits & bit != 0;
+
+                self.bits &= !bit;
+                removed
+            }
+            Err(id) => self.extra.remove(&id),
+        }
+    }
+
+    /// Returns true if the set contains `portal`.
+    pub fn contains(&self, portal: Portal) -> bool {
+        match bit(portal) {
+            Ok(bit) => self.bits & bit != 0,
+            Err(id) => self.extra.contains(&id),
+        }
+    }
+
+    /// Get the number of portals in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize + self.extra.len()
+    }
+
+    /// Returns true if the set contains no portals.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0 && self.extra.is_empty()
+    }
+
+    /// Iterate over the portals in the set, in order of ID.
+    pub fn iter(&self) -> PortalSetIter<'_> {
+        PortalSetIter {
+            bits: self.bits,
+            extra: self.extra.iter(),
+        }
+    }
+
+    /// Get the portals that are in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+            extra: self.extra.union(&other.extra).copied().collect(),
+        }
+    }
+
+    /// Get the portals that are in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+            extra: self.extra.intersection(&other.extra).copied().collect(),
+        }
+    }
+
+    /// Get the portals that are in `self`, but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            bits: self.bits & !other.bits,
+            extra: self.extra.difference(&other.extra).copied().collect(),
+        }
+    }
+
+    /// Returns true if every portal in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.bits & !other.bits == 0 && self.extra.is_subset(&other.extra)
+    }
+}
+
+impl fmt::Debug for PortalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Portal> for PortalSet {
+    fn from_iter<I: IntoIterator<Item = Portal>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Portal> for PortalSet {
+    fn extend<I: IntoIterator<Item = Portal>>(&mut self, iter: I) {
+        for portal in iter {
+            self.insert(portal);
+        }
+    }
+}
+
+impl<const N: usize> From<[Portal; N]> for PortalSet {
+    fn from(portals: [Portal; N]) -> Self {
+        portals.into_iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a PortalSet {
+    type Item = Portal;
+    type IntoIter = PortalSetIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the portals in a [`PortalSet`], in order of ID
+#[derive(Clone, Debug)]
+pub struct PortalSetIter<'a> {
+    bits: u64,
+    extra: btree_set::Iter<'a, u32>,
+}
+
+impl Iterator for PortalSetIter<'_> {
+    type Item = Portal;
+
+    fn next(&mut self) -> Option<Portal> {
+        if self.bits == 0 {
+            return self.extra.next().copied().map(Portal::from);
+        }
+
+        let id = self.bits.trailing_zeros();
+
+        self.bits &= self.bits - 1;
+
+        Some(Portal::from(id))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize + self.extra.len();
+
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for PortalSetIter<'_> {}
+
+/// Get the bit for `portal`, or its ID if it doesn't fit in a `u64` bitmask.
+fn bit(portal: Portal) -> Result<u64, u32> {
+    let id = u32::from(portal);
+
+    1u64.checked_shl(id).ok_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn set_algebra() {
+        let mut set = PortalSet::new();
+
+        assert!(set.insert(Portal::Timer));
+        assert!(set.insert(Portal::Unknown(0x80)));
+        assert!(set.insert(Portal::Unknown(0x40)));
+        assert!(set.insert(Portal::Log));
+        assert!(!set.insert(Portal::Timer));
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.to_bits(), None);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [
+                Portal::Log,
+                Portal::Timer,
+                Portal::Unknown(0x40),
+                Portal::Unknown(0x80),
+            ],
+        );
+
+        let other = PortalSet::from([
+            Portal::Log,
+            Portal::Fetch,
+            Portal::Unknown(0x40),
+        ]);
+
+        assert_eq!(
+            set.union(&other),
+            [set.iter(), other.iter()].into_iter().flatten().collect(),
+        );
+        assert_eq!(
+            set.intersection(&other),
+            PortalSet::from([Portal::Log, Portal::Unknown(0x40)]),
+        );
+        assert_eq!(
+            set.difference(&other),
+            PortalSet::from([Portal::Timer, Portal::Unknown(0x80)]),
+        );
+        assert!(set.intersection(&other).is_subset(&other));
+        assert!(!set.is_subset(&other));
+
+        assert!(set.remove(Portal::Unknown(0x40)));
+        assert!(set.remove(Portal::Unknown(0x80)));
+        assert!(!set.remove(Portal::Fetch));
+        assert!(!set.contains(Portal::Unknown(0x80)));
+        assert_eq!(set.to_bits(), Some(1 << 0x10 | 1));
+        assert_eq!(PortalSet::from_bits(1 << 0x10 | 1), set);
+        assert!(PortalSet::from_bits(0).is_empty());
+    }
+}
@@ -7,116 +7,196 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
 
 use crate::{
-    daku::{Category, Daku, File, Nucleide, Portal},
+    daku::{
+        version, Category, Daku, File, Nucleide, Portal, PortalSet, SpecVersion,
+    },
+    locale::Locale,
     parse::Reader,
     seal::Seal,
     wasm::Read as _,
+    Error, ErrorKind, Result,
 };
 
 /// Daku section reader.
 pub trait Read<'a>: Seal {
-    /// Parse daku section.
-    fn daku(&mut self) -> Option<Daku<'a>>;
+    /// Parse daku section, detecting its layout (see
+    /// [`SpecVersion::detect()`]).
+    fn daku(&mut self) -> Result<Daku<'a>>;
 
-    /// Parse portals list from Daku section.
-    fn portals(&mut self) -> Option<Vec<Portal>>;
+    /// Parse daku section that uses the `version` layout.
+    fn daku_as(&mut self, version: SpecVersion) -> Result<Daku<'a>>;
 
-    /// Parse nucleide extensions subsection.
-    fn nucleide(&mut self) -> Option<Vec<Nucleide<'a>>>;
+    /// Parse portals list from Daku section (duplicates are ignored).
+    fn portals(&mut self) -> Result<PortalSet>;
+
+    /// Parse nucleide extensions subsection, using the `version` layout.
+    fn nucleide(&mut self, version: SpecVersion) -> Result<Vec<Nucleide<'a>>>;
 
     /// Parse file (Nucleide extension).
-    fn file(&mut self) -> Option<File<'a>>;
+    fn file(&mut self) -> Result<File<'a>>;
 
     /// Parse vector of files (Nucleide extension)
-    fn file_vector(&mut self) -> Option<Vec<File<'a>>>;
+    fn file_vector(&mut self) -> Result<Vec<File<'a>>>;
 
     /// Parse map of files (Nucleide extension)
-    fn file_map(&mut self) -> Option<BTreeMap<u32, File<'a>>>;
+    fn file_map(&mut self) -> Result<BTreeMap<Locale, File<'a>>>;
+
+    /// Parse locale, which must be greater than the `previous` locale
+    /// (Nucleide extension).
+    fn locale(&mut self, previous: Option<Locale>) -> Result<Locale>;
+
+    /// Parse map of localized names (Nucleide extension).
+    fn locale_name_map(&mut self) -> Result<BTreeMap<Locale, Cow<'a, str>>>;
 
     /// Parse category (Nucleide extension).
-    fn category(&mut self) -> Option<Category>;
+    fn category(&mut self) -> Result<Category>;
 
     /// Parse category vector (Nucleide extension).
-    fn category_vector(&mut self) -> Option<Vec<Category>>;
+    fn category_vector(&mut self) -> Result<Vec<Category>>;
 }
 
 impl<'a> Read<'a> for Reader<'a> {
-    fn daku(&mut self) -> Option<Daku<'a>> {
-        Some(Daku {
+    fn daku(&mut self) -> Result<Daku<'a>> {
+        let version = version::detect(self);
+
+        self.daku_as(version)
+    }
+
+    fn daku_as(&mut self, version: SpecVersion) -> Result<Daku<'a>> {
+        Ok(Daku {
             portals: self.portals()?,
-            nucleide: if self.end().is_none() {
-                Some(self.nucleide()?)
-            } else {
+            nucleide: if self.is_empty() {
                 None
+            } else {
+                Some(self.nucleide(version)?)
             },
         })
     }
 
-    fn portals(&mut self) -> Option<Vec<Portal>> {
-        let size = self.integer()?.try_into().ok()?;
-
-        (0..size).map(|_| self.integer()?.try_into().ok()).collect()
+    fn portals(&mut self) -> Result<PortalSet> {
+        (0..self.integer()?)
+            .map(|_| self.integer().map(Portal::from))
+            .collect()
     }
 
-    fn nucleide(&mut self) -> Option<Vec<Nucleide<'a>>> {
+    fn nucleide(&mut self, version: SpecVersion) -> Result<Vec<Nucleide<'a>>> {
         let mut subsections = Vec::new();
-        let mut subsection_min = 0;
+        let mut previous = None;
 
-        while self.end().is_none() {
+        while !self.is_empty() {
+            let offset = self.offset();
             let (subsection, mut reader) = self.subsection()?;
 
             // Must be ordered correctly
-            (subsection >= subsection_min).then_some(())?;
-            subsections.push(match subsection {
-                0 => Nucleide::LocalizedNames(reader.name_map()?),
-                1 => Nucleide::LocalizedDescriptions(reader.name_map()?),
-                2 => Nucleide::ThemedIcons(reader.file_vector()?),
-                3 => Nucleide::LocalizedAssets(reader.file_map()?),
-                4 => Nucleide::Tags(reader.name_vector()?),
-                5 => Nucleide::Categories(reader.category_vector()?),
-                6 => Nucleide::Developer(reader.name()?),
-                _ => return None,
-            });
-            reader.end()?;
-            subsection_min = subsection + 1;
+            if previous.is_some_and(|previous| subsection <= previous) {
+                return Err(Error::new(ErrorKind::SubsectionOrder, offset)
+                    .in_subsection(subsection));
+            }
+
+            let id = version.decode(subsection).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::UnknownValue {
+                        what: "subsection",
+                        value: subsection.into(),
+                    },
+                    offset,
+                )
+                .in_subsection(subsection)
+            })?;
+
+            subsections.push(
+                nucleide(id, &mut reader)
+                    .map_err(|e| e.in_subsection(subsection))?,
+            );
+            previous = Some(subsection);
         }
 
-        Some(subsections)
+        Ok(subsections)
     }
 
-    fn file(&mut self) -> Option<File<'a>> {
-        Some(File {
+    fn file(&mut self) -> Result<File<'a>> {
+        Ok(File {
             path: self.name()?,
             data: {
-                let len = self.integer()?.try_into().ok()?;
+                let len = self.length()?;
 
                 self.bytes(len)?.into()
             },
         })
     }
 
-    fn file_vector(&mut self) -> Option<Vec<File<'a>>> {
+    fn file_vector(&mut self) -> Result<Vec<File<'a>>> {
         (0..self.integer()?).map(|_| self.file()).collect()
     }
 
-    fn file_map(&mut self) -> Option<BTreeMap<u32, File<'a>>> {
+    fn file_map(&mut self) -> Result<BTreeMap<Locale, File<'a>>> {
         let mut file_map = BTreeMap::new();
 
         for _ in 0..self.integer()? {
-            file_map.insert(self.integer()?, self.file()?);
+            let locale = self.locale(file_map.keys().next_back().copied())?;
+
+            file_map.insert(locale, self.file()?);
+        }
+
+        Ok(file_map)
+    }
+
+    fn locale(&mut self, previous: Option<Locale>) -> Result<Locale> {
+        let offset = self.offset();
+        let locale = self.index(previous.map(u32::from))?;
+
+        Locale::from_u32(locale).ok_or_else(|| {
+            Error::new(
+                ErrorKind::UnknownValue {
+                    what: "locale",
+                    value: locale,
+                },
+                offset,
+            )
+        })
+    }
+
+    fn locale_name_map(&mut self) -> Result<BTreeMap<Locale, Cow<'a, str>>> {
+        let mut name_map = BTreeMap::new();
+
+        for _ in 0..self.integer()? {
+            let locale = self.locale(name_map.keys().next_back().copied())?;
+
+            name_map.insert(locale, self.name()?);
         }
 
-        Some(file_map)
+        Ok(name_map)
     }
 
-    fn category(&mut self) -> Option<Category> {
-        self.integer()?.try_into().ok()
+    fn category(&mut self) -> Result<Category> {
+        self.integer().map(Category::from)
     }
 
-    fn category_vector(&mut self) -> Option<Vec<Category>> {
+    fn category_vector(&mut self) -> Result<Vec<Category>> {
         (0..self.integer()?).map(|_| self.category()).collect()
     }
 }
+
+/// Parse the contents of the nucleide subsection `id`.
+fn nucleide<'a>(id: u8, reader: &mut Reader<'a>) -> Result<Nucleide<'a>> {
+    let nucleide = match id {
+        1 => Nucleide::LocalizedNames(reader.locale_name_map()?),
+        2 => Nucleide::LocalizedDescriptions(reader.locale_name_map()?),
+        3 => Nucleide::ThemedIcons(reader.file_vector()?),
+        4 => Nucleide::LocalizedAssets(reader.file_map()?),
+        5 => Nucleide::Tags(reader.name_vector()?),
+        6 => Nucleide::Categories(reader.category_vector()?),
+        7 => Nucleide::Developer(reader.name()?),
+        id => Nucleide::Unknown {
+            id,
+            data: reader.rest().into(),
+        },
+    };
+
+    reader.end()?;
+
+    Ok(nucleide)
+}
@@ -9,13 +9,26 @@
 
 use alloc::vec::Vec;
 
-use crate::daku::{Nucleide, Portal};
+use crate::daku::{Nucleide, PortalSet};
 
 /// Daku section
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Daku<'a> {
-    /// List of portals required by the application.
-    pub portals: Vec<Portal>,
+    /// Set of portals required by the application.
+    pub portals: PortalSet,
     /// List of nucleide metadata extension subsections for Nucleic desktop.
     pub nucleide: Option<Vec<Nucleide<'a>>>,
 }
+
+impl Daku<'_> {
+    /// Convert into a daku section that owns all of its data.
+    pub fn into_owned(self) -> Daku<'static> {
+        Daku {
+            portals: self.portals,
+            nucleide: self.nucleide.map(|nucleide| {
+                nucleide.into_iter().map(Nucleide::into_owned).collect()
+            }),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::{
+    daku::{
+        localize::{negotiate, FALLBACK},
+        Localized, Portal, PortalGroup,
+    },
+    locale::Locale,
+};
+
+/// User-facing strings describing portals, in one locale
+///
+/// Implement this to add a translation to [`Translations`].  Strings that are
+/// missing (`None`) fall back to [`English`].
+pub trait Translation {
+    /// Get the locale of the translation.
+    fn locale(&self) -> Locale;
+
+    /// Get the short title of `portal` (such as "Camera").
+    fn portal_title(&self, portal: Portal) -> Option<&str>;
+
+    /// Get a sentence describing what `portal` lets an app do.
+    fn portal_description(&self, portal: Portal) -> Option<&str>;
+
+    /// Get the title of a group of portals (such as "Media capture").
+    fn group_title(&self, group: PortalGroup) -> Option<&str>;
+}
+
+/// Built-in English (`enUS`) translation
+#[derive(Debug, Copy, Clone, Default)]
+pub struct English;
+
+impl Translation for English {
+    fn locale(&self) -> Locale {
+        FALLBACK
+    }
+
+    fn portal_title(&self, portal: Portal) -> Option<&str> {
+        Some(portal.title())
+    }
+
+    fn portal_description(&self, portal: Portal) -> Option<&str> {
+        Some(portal.description())
+    }
+
+    fn group_title(&self, group: PortalGroup) -> Option<&str> {
+        Some(group.title())
+    }
+}
+
+/// Set of [`Translation`]s to pick portal strings from
+///
+/// ```rust
+/// use nucleide::{
+///     daku::{Portal, PortalGroup, Translation, Translations},
+///     Locale,
+/// };
+///
+/// struct French;
+///
+/// impl Translation for French {
+///     fn locale(&self) -> Locale {
+///         "fr-FR".parse().unwrap()
+///     }
+///
+///     fn portal_title(&self, portal: Portal) -> Option<&str> {
+///         match portal {
+///             Portal::Camera => Some("Caméra"),
+///             _ => None,
+///         }
+///     }
+///
+///     fn portal_description(&self, _portal: Portal) -> Option<&str> {
+///         None
+///     }
+///
+///     fn group_title(&self, _group: PortalGroup) -> Option<&str> {
+///         None
+///     }
+/// }
+///
+/// let mut translations = Translations::new();
+/// let fr_ca = "fr-CA".parse().unwrap();
+///
+/// translations.insert(&French);
+///
+/// assert_eq!(translations.portal_title(Portal::Camera, &[fr_ca]).value, "Caméra");
+/// assert_eq!(translations.portal_title(Portal::Fetch, &[fr_ca]).value, "Network");
+/// ```
+#[derive(Clone)]
+pub struct Translations<'a> {
+    translations: BTreeMap<Locale, &'a dyn Translation>,
+}
+
+impl<'a> Translations<'a> {
+    /// Create a set containing only the [`English`] translation.
+    pub fn new() -> Self {
+        let english: &'a dyn Translation = &English;
+
+        Self {
+            translations: BTreeMap::from([(FALLBACK, english)]),
+        }
+    }
+
+    /// Add a translation, returning the one it replaced for the same locale
+    /// (if any).
+    pub fn insert(
+        &mut self,
+        translation: &'a dyn Translation,
+    ) -> Option<&'a dyn Translation> {
+        self.translations.insert(translation.locale(), translation)
+    }
+
+    /// Pick the best title of `portal` for a user's `preferred` locales (most
+    /// preferred first), as in [`Daku::name()`](crate::daku::Daku::name).
+    pub fn portal_title(
+        &self,
+        portal: Portal,
+        preferred: &[Locale],
+    ) -> Localized<&str> {
+        self.pick(preferred, |translation| translation.portal_title(portal))
+    }
+
+    /// Pick the best description of `portal` for a user's `preferred` locales.
+    pub fn portal_description(
+        &self,
+        portal: Portal,
+        preferred: &[Locale],
+    ) -> Localized<&str> {
+        self.pick(preferred, |translation| {
+            translation.portal_description(portal)
+        })
+    }
+
+    /// Pick the best title of `group` for a user's `preferred` locales.
+    pub fn group_title(
+        &self,
+        group: PortalGroup,
+        preferred: &[Locale],
+    ) -> Localized<&str> {
+        self.pick(preferred, |translation| translation.group_title(group))
+    }
+
+    /// Get a string from the best translation, falling back to English if it
+    /// doesn't have it.
+    fn pick<'s>(
+        &'s self,
+        preferred: &[Locale],
+        get: impl for<'t> Fn(&'t dyn Translation) -> Option<&'t str>,
+    ) -> Localized<&'s str> {
+        negotiate(&self.translations, preferred)
+            .and_then(|(locale, translation)| {
+                Some(Localized {
+                    locale,
+                    value: get(*translation)?,
+                })
+            })
+            .unwrap_or_else(|| Localized {
+                locale: FALLBACK,
+                value: get(&English).unwrap_or_default(),
+            })
+    }
+}
+
+impl fmt::Debug for Translations<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.translations.keys()).finish()
+    }
+}
+
+impl Default for Translations<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Portal {
+    /// Get the short English title of the portal.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Log => "Logging",
+            Self::Prompt => "Developer commands",
+            Self::Fetch => "Network",
+            Self::Serve => "Network server",
+            Self::Speakers => "Speakers",
+            Self::Microphone => "Microphone",
+            Self::Screen => "Screen recording",
+            Self::Camera => "Camera",
+            Self::Window => "Windows",
+            Self::Spawn => "Launch apps",
+            Self::User => "Change user information",
+            Self::Preferences => "User information",
+            Self::System => "System settings",
+            Self::About => "System information",
+            Self::File => "Files",
+            Self::Hid => "Input devices",
+            Self::Timer => "Timers",
+            Self::Clock => "Date and time",
+            Self::Gpu => "Graphics",
+            Self::Location => "Location",
+            Self::Unknown(_) => "Unknown portal",
+        }
+    }
+
+    /// Get an English sentence describing what the portal lets an app do.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Log => "Write messages to the developer log.",
+            Self::Prompt => "Read commands typed in by a developer.",
+            Self::Fetch => "Connect to servers on the internet.",
+            Self::Serve => {
+                "Accept connections from other devices on the network."
+            }
+            Self::Speakers => "Play sound.",
+            Self::Microphone => "Record audio with your microphone.",
+            Self::Screen => "Record everything shown on your screen.",
+            Self::Camera => "Take pictures and record video with your camera.",
+            Self::Window => "Open windows and draw in them.",
+            Self::Spawn => "Launch other apps and background tasks.",
+            Self::User => {
+                "Change your username, display name and language settings."
+            }
+            Self::Preferences => {
+                "See your username, display name and language settings."
+            }
+            Self::System => "Create users, and change settings for all users.",
+            Self::About => {
+                "See information about this device and its settings."
+            }
+            Self::File => "Read and write files.",
+            Self::Hid => {
+                "Read input from your keyboard, mouse, gamepads and other \
+                 devices."
+            }
+            Self::Timer => "Wait, and run tasks at regular intervals.",
+            Self::Clock => "See the current date and time.",
+            Self::Gpu => "Use the graphics card to draw and compute.",
+            Self::Location => "See where you are.",
+            Self::Unknown(_) => {
+                "Use a feature from a newer version of Daku that isn't \
+                 recognized."
+            }
+        }
+    }
+}
+
+impl PortalGroup {
+    /// Get the English title of the group.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Console => "Developer console",
+            Self::Network => "Network",
+            Self::Output => "Sound and graphics",
+            Self::MediaCapture => "Media capture",
+            Self::Apps => "Other apps",
+            Self::Account => "User account",
+            Self::System => "System",
+            Self::Files => "Files",
+            Self::Input => "Input devices",
+            Self::Time => "Date and time",
+            Self::Location => "Location",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Translation with only the title of [`Portal::Camera`]
+    struct Partial(Locale, &'static str);
+
+    impl Translation for Partial {
+        fn locale(&self) -> Locale {
+            self.0
+        }
+
+        fn portal_title(&self, portal: Portal) -> Option<&str> {
+            (portal == Portal::Camera).then_some(self.1)
+        }
+
+        fn portal_description(&self, _portal: Portal) -> Option<&str> {
+            None
+        }
+
+        fn group_title(&self, _group: PortalGroup) -> Option<&str> {
+            None
+        }
+    }
+
+    #[test]
+    fn translations() {
+        let [fr_fr, en_us, en_gb] =
+            ["frFR", "enUS", "enGB"].map(|locale| locale.parse().unwrap());
+        let french = Partial(fr_fr, "Caméra");
+        let american = Partial(en_us, "Webcam");
+        let mut translations = Translations::new();
+
+        assert!(translations.insert(&french).is_none());
+        assert_eq!(
+            translations.portal_title(Portal::Camera, &[fr_fr]),
+            Localized {
+                locale: fr_fr,
+                value: "Caméra",
+            },
+        );
+        assert_eq!(
+            translations.group_title(PortalGroup::MediaCapture, &[fr_fr]),
+            Localized {
+                locale: en_us,
+                value: "Media capture",
+            },
+        );
+        assert_eq!(
+            translations
+                .portal_description(Portal::Camera, &[en_gb])
+                .value,
+            Portal::Camera.description(),
+        );
+
+        assert!(translations.insert(&american).is_some());
+        assert_eq!(
+            translations.portal_title(Portal::Camera, &[en_gb]).value,
+            "Webcam",
+        );
+        assert_eq!(
+            translations.portal_title(Portal::Fetch, &[en_gb]).value,
+            "Network",
+        );
+        assert!(Portal::Camera.risk() > Portal::Fetch.risk());
+        assert_eq!(Portal::Unknown(0x80).group(), PortalGroup::Unknown);
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    daku::{Daku, Portal, PortalSet},
+    wasm::{Import, ImportKind},
+    Error, ErrorKind, Module, Result,
+};
+
+/// Portals a module's code imports, compared with the portals its `daku`
+/// section declares
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PortalUsage {
+    /// Portals the module imports
+    pub used: PortalSet,
+    /// Portals listed in the `daku` section
+    pub declared: PortalSet,
+}
+
+impl PortalUsage {
+    /// Get the portals that are declared, but never imported (the app is
+    /// asking for more permissions than it needs).
+    pub fn unused(&self) -> PortalSet {
+        self.declared.difference(&self.used)
+    }
+
+    /// Get the portals that are imported, but not declared (the app won't be
+    /// given permission to use them).
+    pub fn undeclared(&self) -> PortalSet {
+        self.used.difference(&self.declared)
+    }
+
+    /// Returns true if the declared portals are exactly the ones used.
+    pub fn is_exact(&self) -> bool {
+        self.used == self.declared
+    }
+}
+
+impl Portal {
+    /// Get the portal an import requires, using the default table.
+    ///
+    /// Bindings that import a function per portal import it from the `daku`
+    /// module, named after the portal:
+    ///
+    /// | Import             | Portal                  |
+    /// |--------------------|-------------------------|
+    /// | `daku.log`         | [`Portal::Log`]         |
+    /// | `daku.prompt`      | [`Portal::Prompt`]      |
+    /// | `daku.fetch`       | [`Portal::Fetch`]       |
+    /// | `daku.serve`       | [`Portal::Serve`]       |
+    /// | `daku.speakers`    | [`Portal::Speakers`]    |
+    /// | `daku.microphone`  | [`Portal::Microphone`]  |
+    /// | `daku.screen`      | [`Portal::Screen`]      |
+    /// | `daku.camera`      | [`Portal::Camera`]      |
+    /// | `daku.window`      | [`Portal::Window`]      |
+    /// | `daku.spawn`       | [`Portal::Spawn`]       |
+    /// | `daku.user`        | [`Portal::User`]        |
+    /// | `daku.preferences` | [`Portal::Preferences`] |
+    /// | `daku.system`      | [`Portal::System`]      |
+    /// | `daku.about`       | [`Portal::About`]       |
+    /// | `daku.file`        | [`Portal::File`]        |
+    /// | `daku.hid`         | [`Portal::Hid`]         |
+    /// | `daku.timer`       | [`Portal::Timer`]       |
+    /// | `daku.clock`       | [`Portal::Clock`]       |
+    /// | `daku.gpu`         | [`Portal::Gpu`]         |
+    /// | `daku.location`    | [`Portal::Location`]    |
+    ///
+    /// Any other import (including `daku.ar`, which every portal is reached
+    /// through) doesn't require a portal.  Only functions are mapped.
+    pub fn from_import(import: &Import<'_>) -> Option<Portal> {
+        if import.module != "daku"
+            || !matches!(import.kind, ImportKind::Function(_))
+        {
+            return None;
+        }
+
+        Some(match &*import.name {
+            "log" => Self::Log,
+            "prompt" => Self::Prompt,
+            "fetch" => Self::Fetch,
+            "serve" => Self::Serve,
+            "speakers" => Self::Speakers,
+            "microphone" => Self::Microphone,
+            "screen" => Self::Screen,
+            "camera" => Self::Camera,
+            "window" => Self::Window,
+            "spawn" => Self::Spawn,
+            "user" => Self::User,
+            "preferences" => Self::Preferences,
+            "system" => Self::System,
+            "about" => Self::About,
+            "file" => Self::File,
+            "hid" => Self::Hid,
+            "timer" => Self::Timer,
+            "clock" => Self::Clock,
+            "gpu" => Self::Gpu,
+            "location" => Self::Location,
+            _ => return None,
+        })
+    }
+}
+
+impl Module {
+    /// Compare the portals the module imports with the ones its `daku`
+    /// section declares, using `portal` to find the portal each import
+    /// requires (if any).
+    ///
+    /// Pass [`Portal::from_import`] to use the default table, or a closure to
+    /// override it for bindings that import portals differently (modules that
+    /// only import `daku.ar` don't say which portals they use).  A missing
+    /// import section or `daku` section counts as empty.
+    pub fn portal_usage(
+        &self,
+        portal: impl FnMut(&Import<'_>) -> Option<Portal>,
+    ) -> Result<PortalUsage> {
+        let imports = self.imports()?.unwrap_or_default();
+
+        Ok(PortalUsage {
+            used: imports.iter().filter_map(portal).collect(),
+            declared: self.daku()?.map(|daku| daku.portals).unwrap_or_default(),
+        })
+    }
+
+    /// Replace the portals in the `daku` section with the ones the module
+    /// imports, as found by `portal` (see [`Module::portal_usage()`]), adding
+    /// the section if needed.
+    ///
+    /// Returns the usage from before the portals were replaced.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::NoPortalImports`] error (leaving the section
+    /// unchanged) if none of the imports map to a portal.
+    pub fn infer_portals(
+        &mut self,
+        portal: impl FnMut(&Import<'_>) -> Option<Portal>,
+    ) -> Result<PortalUsage> {
+        let usage = self.portal_usage(portal)?;
+
+        if usage.used.is_empty() {
+            return Err(Error::new(ErrorKind::NoPortalImports, 0));
+        }
+
+        if usage.is_exact() {
+            return Ok(usage);
+        }
+
+        let mut daku = self.daku()?.map(Daku::into_owned).unwrap_or(Daku {
+            portals: PortalSet::new(),
+            nucleide: None,
+        });
+
+        daku.portals = usage.used.clone();
+        self.set_daku(&daku)?;
+
+        Ok(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Section;
+
+    /// Module importing `daku.ar`, `daku.fetch`, `daku.camera_open`, `env.log`
+    /// and `env.mem`
+    #[rustfmt::skip]
+    const MODULE: &[u8] = &[
+        // Header
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+        // Type section
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+        // Import section
+        0x02, 0x41, 0x05,
+        0x04, b'd', b'a', b'k', b'u', 0x02, b'a', b'r', 0x00, 0x00,
+        0x04, b'd', b'a', b'k', b'u', 0x05, b'f', b'e', b't', b'c', b'h',
+        0x00, 0x00,
+        0x04, b'd', b'a', b'k', b'u', 0x0B, b'c', b'a', b'm', b'e', b'r', b'a',
+        b'_', b'o', b'p', b'e', b'n', 0x00, 0x00,
+        0x03, b'e', b'n', b'v', 0x03, b'l', b'o', b'g', 0x00, 0x00,
+        0x03, b'e', b'n', b'v', 0x03, b'm', b'e', b'm', 0x02, 0x01, 0x01, 0x02,
+    ];
+
+    /// Map `daku.camera_open` to a portal, as well as the default table.
+    fn portal(import: &Import<'_>) -> Option<Portal> {
+        match (&*import.module, &*import.name) {
+            ("daku", "camera_open") => Some(Portal::Camera),
+            _ => Portal::from_import(import),
+        }
+    }
+
+    #[test]
+    fn portal_usage() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        assert_eq!(module.imports().unwrap().unwrap().len(), 5);
+
+        module
+            .set_section(Section::Daku(Daku {
+                portals: PortalSet::from([Portal::Fetch, Portal::Log]),
+                nucleide: None,
+            }))
+            .unwrap();
+
+        let usage = module.portal_usage(portal).unwrap();
+
+        assert_eq!(
+            usage.used,
+            PortalSet::from([Portal::Fetch, Portal::Camera])
+        );
+        assert_eq!(usage.unused(), PortalSet::from([Portal::Log]));
+        assert_eq!(usage.undeclared(), PortalSet::from([Portal::Camera]));
+        assert!(!usage.is_exact());
+        assert_eq!(module.infer_portals(portal).unwrap(), usage);
+        assert!(module.portal_usage(portal).unwrap().is_exact());
+
+        // `daku.camera_open` isn't in the default table
+        let usage = module.portal_usage(Portal::from_import).unwrap();
+
+        assert_eq!(usage.used, PortalSet::from([Portal::Fetch]));
+    }
+
+    #[test]
+    fn no_portal_imports() {
+        let mut module = Module::new(MODULE).unwrap();
+        let daku = Daku {
+            portals: PortalSet::from([Portal::Fetch]),
+            nucleide: None,
+        };
+
+        module.set_daku(&daku).unwrap();
+
+        // Only `daku.ar`, which doesn't map to any portal
+        let error = module.infer_portals(|_| None).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NoPortalImports);
+        assert_eq!(module.daku().unwrap().unwrap(), daku);
+    }
+}
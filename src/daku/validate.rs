@@ -0,0 +1,398 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::daku::{Category, Daku, Nucleide, Portal};
+
+/// Maximum number of searchable tags
+const MAX_TAGS: usize = 8;
+/// Maximum number of categories
+const MAX_CATEGORIES: usize = 2;
+/// Standard icon theme names
+const THEMES: [&str; 2] = ["default", "reduced"];
+
+/// How serious a [`Violation`] is
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Severity {
+    /// Allowed, but likely a mistake
+    Warning,
+    /// Not allowed by the specification
+    Error,
+}
+
+/// A rule from the specification that a `daku` section breaks
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// Portal isn't recognized (it may be from a newer version of Daku)
+    UnrecognizedPortal(u32),
+    /// More than 8 tags
+    TooManyTags(usize),
+    /// Tag isn't lowercase ASCII English words separated by single spaces
+    InvalidTag(String),
+    /// More than 2 categories
+    TooManyCategories(usize),
+    /// The same category is listed more than once
+    DuplicateCategory(Category),
+    /// Category isn't recognized (it may be from a newer specification)
+    UnrecognizedCategory(u32),
+    /// Icon theme isn't `"default"` or `"reduced"`
+    UnknownTheme(String),
+    /// Icon theme data isn't a list of QOI files
+    InvalidIcon(String),
+    /// The same icon theme is listed more than once
+    DuplicateTheme(String),
+    /// Two icons in the same theme have the same resolution
+    DuplicateResolution {
+        /// Icon theme name
+        theme: String,
+        /// Width in pixels
+        width: u32,
+        /// Height in pixels
+        height: u32,
+    },
+    /// The same subsection appears more than once, or out of order
+    SubsectionOrder,
+}
+
+impl Violation {
+    /// Get how serious the violation is.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnrecognizedPortal(_)
+            | Self::DuplicateCategory(_)
+            | Self::UnrecognizedCategory(_)
+            | Self::InvalidIcon(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{severity}: ")?;
+
+        match self {
+            Self::UnrecognizedPortal(id) => {
+                write!(f, "portal {id:#x} isn't recognized")
+            }
+            Self::TooManyTags(count) => {
+                write!(f, "{count} tags (limit {MAX_TAGS})")
+            }
+            Self::InvalidTag(tag) => write!(
+                f,
+                "tag {tag:?} isn't lowercase ASCII words separated by spaces",
+            ),
+            Self::TooManyCategories(count) => {
+                write!(f, "{count} categories (limit {MAX_CATEGORIES})")
+            }
+            Self::DuplicateCategory(category) => {
+                write!(f, "category {category:?} is listed more than once")
+            }
+            Self::UnrecognizedCategory(id) => {
+                write!(f, "category {id:#x} isn't recognized")
+            }
+            Self::UnknownTheme(theme) => write!(
+                f,
+                "icon theme {theme:?} isn't \"default\" or \"reduced\"",
+            ),
+            Self::InvalidIcon(theme) => {
+                write!(f, "icon theme {theme:?} isn't a list of QOI files")
+            }
+            Self::DuplicateTheme(theme) => {
+                write!(f, "icon theme {theme:?} is listed more than once")
+            }
+            Self::DuplicateResolution {
+                theme,
+                width,
+                height,
+            } => write!(
+                f,
+                "icon theme {theme:?} has more than one {width}×{height} icon",
+            ),
+            Self::SubsectionOrder => {
+                f.write_str("nucleide subsections are repeated or out of order")
+            }
+        }
+    }
+}
+
+impl Daku<'_> {
+    /// Check the section against the rules in the specification, returning
+    /// every violation found.
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for portal in &self.portals {
+            if let Portal::Unknown(id) = portal {
+                violations.push(Violation::UnrecognizedPortal(id));
+            }
+        }
+
+        let mut previous = None;
+
+        for subsection in self.nucleide.iter().flatten() {
+            let id = subsection.id();
+
+            if previous.is_some_and(|previous| id <= previous) {
+                violations.push(Violation::SubsectionOrder);
+            }
+
+            previous = Some(id);
+
+            match subsection {
+                Nucleide::Tags(tags) => {
+                    if tags.len() > MAX_TAGS {
+                        violations.push(Violation::TooManyTags(tags.len()));
+                    }
+
+                    for tag in tags.iter().filter(|tag| !valid_tag(tag)) {
+                        violations.push(Violation::InvalidTag(tag.to_string()));
+                    }
+                }
+                Nucleide::Categories(categories) => {
+                    if categories.len() > MAX_CATEGORIES {
+                        violations.push(Violation::TooManyCategories(
+                            categories.len(),
+                        ));
+                    }
+
+                    for (i, category) in categories.iter().enumerate() {
+                        if categories[..i].contains(category) {
+                            violations
+                                .push(Violation::DuplicateCategory(*category));
+                        }
+
+                        if let Category::Unknown(id) = category {
+                            violations
+                                .push(Violation::UnrecognizedCategory(*id));
+                        }
+                    }
+                }
+                Nucleide::ThemedIcons(icons) => {
+                    // Resolutions of each theme, across repeated entries
+                    let mut themes = BTreeMap::<&str, Vec<(u32, u32)>>::new();
+
+                    for icon in icons {
+                        let theme = &*icon.path;
+
+                        if !THEMES.contains(&theme) {
+                            violations
+                                .push(Violation::UnknownTheme(theme.into()));
+                        }
+
+                        if themes.contains_key(theme) {
+                            violations
+                                .push(Violation::DuplicateTheme(theme.into()));
+                        }
+
+                        let seen = themes.entry(theme).or_default();
+                        let Some(resolutions) = qoi_resolutions(&icon.data)
+                        else {
+                            violations
+                                .push(Violation::InvalidIcon(theme.into()));
+                            continue;
+                        };
+
+                        for (width, height) in resolutions {
+                            if seen.contains(&(width, height)) {
+                                violations.push(
+                                    Violation::DuplicateResolution {
+                                        theme: theme.into(),
+                                        width,
+                                        height,
+                                    },
+                                );
+                            }
+
+                            seen.push((width, height));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        violations
+    }
+
+    /// Returns true if the section doesn't break any rule with
+    /// [`Severity::Error`].
+    pub fn is_valid(&self) -> bool {
+        self.validate()
+            .iter()
+            .all(|violation| violation.severity() < Severity::Error)
+    }
+}
+
+/// Lowercase ASCII words separated by single spaces
+fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.split(' ').all(|word| {
+            !word.is_empty() && word.bytes().all(|b| b.is_ascii_lowercase())
+        })
+}
+
+/// Get the resolution of each file in a list of concatenated QOI files.
+fn qoi_resolutions(mut data: &[u8]) -> Option<Vec<(u32, u32)>> {
+    const END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+    let mut resolutions = Vec::new();
+
+    while !data.is_empty() {
+        let header = data.get(..14)?;
+
+        if &header[..4] != b"qoif" {
+            return None;
+        }
+
+        let width = u32::from_be_bytes(header[4..8].try_into().ok()?);
+        let height = u32::from_be_bytes(header[8..12].try_into().ok()?);
+
+        resolutions.push((width, height));
+
+        // Find the end marker that's followed by the next file (or nothing)
+        let end = (14..=data.len().checked_sub(END.len())?).find(|&i| {
+            let rest = &data[i + END.len()..];
+
+            data[i..].starts_with(&END)
+                && (rest.is_empty() || rest.starts_with(b"qoif"))
+        })?;
+
+        data = &data[end + END.len()..];
+    }
+
+    (!resolutions.is_empty()).then_some(resolutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        daku::{File, PortalSet, Write as _},
+        parse::Writer,
+        ErrorKind,
+    };
+
+    fn qoi(width: u8, height: u8) -> Vec<u8> {
+        let mut data = b"qoif".to_vec();
+
+        data.extend([0, 0, 0, width, 0, 0, 0, height, 4, 0]);
+        data.extend([0xFE, 1, 2, 3]);
+        data.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+        data
+    }
+
+    #[test]
+    fn validate() {
+        let mut icons = qoi(16, 16);
+
+        icons.extend(qoi(32, 32));
+        icons.extend(qoi(16, 16));
+
+        let daku = Daku {
+            portals: PortalSet::from([Portal::Fetch, Portal::Unknown(0x14)]),
+            nucleide: Some(vec![
+                Nucleide::ThemedIcons(vec![
+                    File {
+                        path: "default".into(),
+                        data: icons.into(),
+                    },
+                    File {
+                        path: "dark".into(),
+                        data: qoi(16, 16).into(),
+                    },
+                ]),
+                Nucleide::Tags(vec![
+                    "text editor".into(),
+                    "Notes".into(),
+                    "to-do".into(),
+                ]),
+                Nucleide::Categories(vec![
+                    Category::Office,
+                    Category::Life,
+                    Category::Coding,
+                ]),
+            ]),
+        };
+
+        assert_eq!(
+            daku.validate(),
+            [
+                Violation::UnrecognizedPortal(0x14),
+                Violation::DuplicateResolution {
+                    theme: "default".into(),
+                    width: 16,
+                    height: 16,
+                },
+                Violation::UnknownTheme("dark".into()),
+                Violation::InvalidTag("Notes".into()),
+                Violation::InvalidTag("to-do".into()),
+                Violation::TooManyCategories(3),
+            ],
+        );
+        assert!(!daku.is_valid());
+
+        let mut buffer = Vec::new();
+        let error = Writer::new(&mut buffer).daku_strict(&daku).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Invalid);
+        assert!(buffer.is_empty());
+        assert!(Daku {
+            portals: PortalSet::from([Portal::Fetch]),
+            nucleide: None,
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn repeated_theme() {
+        let icon = |data: Vec<u8>| File {
+            path: "default".into(),
+            data: data.into(),
+        };
+        let mut icons = qoi(16, 16);
+
+        icons.extend(qoi(32, 32));
+
+        let daku = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::ThemedIcons(vec![
+                icon(icons),
+                icon(qoi(32, 32)),
+            ])]),
+        };
+
+        assert_eq!(
+            daku.validate(),
+            [
+                Violation::DuplicateTheme("default".into()),
+                Violation::DuplicateResolution {
+                    theme: "default".into(),
+                    width: 32,
+                    height: 32,
+                },
+            ],
+        );
+        assert!(!daku.is_valid());
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{daku::Read as _, parse::Reader, wasm::Read as _, Result};
+
+/// Layout of the nucleide subsection IDs in a `daku` section
+///
+/// [`Nucleide`](crate::daku::Nucleide) subsection IDs are always numbered as
+/// in [`SpecVersion::V1`]; the layout only changes how they are encoded.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum SpecVersion {
+    /// Subsection IDs `0`–`6`, as written by earlier versions of this crate
+    Legacy,
+    /// Subsection IDs `1`–`7`, with `0` reserved for a breaking 2.0 version
+    /// of Daku (as in the specification)
+    #[default]
+    V1,
+}
+
+impl SpecVersion {
+    /// Detect which layout the contents of a `daku` section use.
+    ///
+    /// Only the [`Legacy`](Self::Legacy) layout has a subsection `0`, so a
+    /// section with one is `Legacy`, and any other section is assumed to be
+    /// [`V1`](Self::V1).  The two layouts can't be told apart without
+    /// subsection `0` (a `V1` list of categories is also a valid `Legacy`
+    /// developer name), so read such sections with
+    /// [`daku_as()`](crate::daku::Read::daku_as) if the layout is known to be
+    /// `Legacy`.
+    pub fn detect(section: &[u8]) -> Self {
+        detect(&Reader::new(section))
+    }
+
+    /// Get the encoded ID of the subsection `id`, if it can be encoded.
+    pub(crate) fn encode(self, id: u8) -> Option<u8> {
+        match self {
+            Self::Legacy => id.checked_sub(1),
+            Self::V1 => Some(id),
+        }
+    }
+
+    /// Get the subsection ID from its encoded ID, if it can be decoded.
+    pub(crate) fn decode(self, id: u8) -> Option<u8> {
+        match self {
+            Self::Legacy => id.checked_add(1),
+            Self::V1 => Some(id),
+        }
+    }
+}
+
+/// Detect the layout of the `daku` section being read by `reader` (without
+/// advancing it).
+pub(crate) fn detect(reader: &Reader<'_>) -> SpecVersion {
+    let mut reader = reader.clone();
+    let mut has_zero = || -> Result<bool> {
+        reader.portals()?;
+
+        while !reader.is_empty() {
+            if reader.subsection()?.0 == 0 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    };
+
+    match has_zero() {
+        Ok(true) => SpecVersion::Legacy,
+        _ => SpecVersion::V1,
+    }
+}
@@ -7,25 +7,40 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use alloc::collections::BTreeMap;
+use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
 
 use crate::{
-    daku::{Category, Daku, File, Nucleide, Portal},
+    daku::{Category, Daku, File, Nucleide, PortalSet, SpecVersion},
+    locale::Locale,
     parse::Writer,
     seal::Seal,
     wasm::Write as _,
+    Error, ErrorKind, Result,
 };
 
 /// Daku section  writer.
 pub trait Write<'a>: Seal {
-    /// Write out daku section.
-    fn daku(&mut self, daku: &Daku<'_>) -> Option<()>;
+    /// Write out daku section, using the default layout
+    /// ([`SpecVersion::V1`]).
+    fn daku(&mut self, daku: &Daku<'_>) -> Result;
 
-    /// Write out portals list from Daku section.
-    fn portals(&mut self, portals: &[Portal]);
+    /// Write out daku section, using the `version` layout.
+    fn daku_as(&mut self, daku: &Daku<'_>, version: SpecVersion) -> Result;
 
-    /// Write out nucleide extension subsections.
-    fn nucleide(&mut self, subsections: &[Nucleide<'_>]) -> Option<()>;
+    /// Write out daku section, refusing to if it breaks any rule with
+    /// [`Severity::Error`](crate::daku::Severity::Error) (see
+    /// [`Daku::validate()`]).
+    fn daku_strict(&mut self, daku: &Daku<'_>) -> Result;
+
+    /// Write out portals list from Daku section (sorted by ID).
+    fn portals(&mut self, portals: &PortalSet);
+
+    /// Write out nucleide extension subsections, using the `version` layout.
+    fn nucleide(
+        &mut self,
+        subsections: &[Nucleide<'_>],
+        version: SpecVersion,
+    ) -> Result;
 
     /// Write out file (Nucleide extension).
     fn file(&mut self, file: &File<'_>);
@@ -34,66 +49,116 @@ pub trait Write<'a>: Seal {
     fn file_vector(&mut self, files: &[File<'_>]);
 
     /// Write out map of files (Nucleide extension)
-    fn file_map(&mut self, files: &BTreeMap<u32, File<'_>>);
+    fn file_map(&mut self, files: &BTreeMap<Locale, File<'_>>);
+
+    /// Write out locale (Nucleide extension).
+    fn locale(&mut self, locale: Locale);
+
+    /// Write out map of localized names (Nucleide extension).
+    fn locale_name_map(&mut self, names: &BTreeMap<Locale, Cow<'_, str>>);
 
     /// Write out category (Nucleide extension).
-    fn category(&mut self, category: Category);
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::Invalid`] error for a [`Category::Unknown`]
+    /// with the ID of a recognized category, since it would be read back as
+    /// that category.
+    fn category(&mut self, category: Category) -> Result;
 
     /// Write out category vector (Nucleide extension).
-    fn category_vector(&mut self, categories: &[Category]);
+    fn category_vector(&mut self, categories: &[Category]) -> Result;
 }
 
 impl<'a> Write<'a> for Writer<'a> {
-    fn daku(&mut self, daku: &Daku<'_>) -> Option<()> {
-        self.portals(daku.portals.as_slice());
+    fn daku(&mut self, daku: &Daku<'_>) -> Result {
+        self.daku_as(daku, SpecVersion::default())
+    }
+
+    fn daku_as(&mut self, daku: &Daku<'_>, version: SpecVersion) -> Result {
+        self.portals(&daku.portals);
 
         if let Some(ref nucleide) = daku.nucleide {
-            self.nucleide(nucleide.as_slice())?;
+            self.nucleide(nucleide.as_slice(), version)?;
         }
 
-        Some(())
+        Ok(())
     }
 
-    fn portals(&mut self, portals: &[Portal]) {
+    fn daku_strict(&mut self, daku: &Daku<'_>) -> Result {
+        if !daku.is_valid() {
+            return Err(Error::new(ErrorKind::Invalid, self.offset())
+                .in_section("daku"));
+        }
+
+        self.daku(daku)
+    }
+
+    fn portals(&mut self, portals: &PortalSet) {
         // Write vector length
         self.integer(portals.len().try_into().unwrap_or(u32::MAX));
 
-        for portal in portals.iter().copied() {
+        for portal in portals {
             self.integer(portal.into());
         }
     }
 
-    fn nucleide(&mut self, subsections: &[Nucleide<'_>]) -> Option<()> {
-        let mut subsection_min = 0;
+    fn nucleide(
+        &mut self,
+        subsections: &[Nucleide<'_>],
+        version: SpecVersion,
+    ) -> Result {
+        let mut previous = None;
 
         for subsection in subsections {
-            let id = match subsection {
-                Nucleide::LocalizedNames(_) => 0,
-                Nucleide::LocalizedDescriptions(_) => 1,
-                Nucleide::ThemedIcons(_) => 2,
-                Nucleide::LocalizedAssets(_) => 3,
-                Nucleide::Tags(_) => 4,
-                Nucleide::Categories(_) => 5,
-                Nucleide::Developer(_) => 6,
-            };
+            let id = subsection.id();
 
             // Must be ordered correctly
-            (id >= subsection_min).then_some(())?;
-            subsection_min = id + 1;
-            self.u8(id);
+            if previous.is_some_and(|previous| id <= previous) {
+                return Err(Error::new(
+                    ErrorKind::SubsectionOrder,
+                    self.offset(),
+                )
+                .in_subsection(id));
+            }
+
+            previous = Some(id);
+
+            // Subsection can't be encoded in this layout
+            let Some(encoded) = version.encode(id) else {
+                return Err(Error::new(
+                    ErrorKind::UnknownValue {
+                        what: "subsection",
+                        value: id.into(),
+                    },
+                    self.offset(),
+                )
+                .in_subsection(id));
+            };
+
+            let mut buffer = Vec::new();
+            let mut writer = Writer::new(&mut buffer);
 
             match subsection {
-                Nucleide::LocalizedNames(data) => self.name_map(data),
-                Nucleide::LocalizedDescriptions(data) => self.name_map(data),
-                Nucleide::ThemedIcons(data) => self.file_vector(data),
-                Nucleide::LocalizedAssets(data) => self.file_map(data),
-                Nucleide::Tags(data) => self.name_vector(data),
-                Nucleide::Categories(data) => self.category_vector(data),
-                Nucleide::Developer(data) => self.name(data),
+                Nucleide::LocalizedNames(data) => writer.locale_name_map(data),
+                Nucleide::LocalizedDescriptions(data) => {
+                    writer.locale_name_map(data)
+                }
+                Nucleide::ThemedIcons(data) => writer.file_vector(data),
+                Nucleide::LocalizedAssets(data) => writer.file_map(data),
+                Nucleide::Tags(data) => writer.name_vector(data),
+                Nucleide::Categories(data) => {
+                    writer.category_vector(data).map_err(|e| {
+                        Error::new(e.kind(), self.offset()).in_subsection(id)
+                    })?
+                }
+                Nucleide::Developer(data) => writer.name(data),
+                Nucleide::Unknown { data, .. } => writer.bytes(data),
             };
+
+            self.subsection(encoded, &buffer);
         }
 
-        Some(())
+        Ok(())
     }
 
     fn file(&mut self, file: &File<'_>) {
@@ -109,26 +174,48 @@ impl<'a> Write<'a> for Writer<'a> {
         files.iter().for_each(|file| self.file(file));
     }
 
-    fn file_map(&mut self, files: &BTreeMap<u32, File<'_>>) {
+    fn file_map(&mut self, files: &BTreeMap<Locale, File<'_>>) {
         // Write map length
         self.integer(files.len().try_into().unwrap_or(u32::MAX));
 
         files.iter().for_each(|(k, v)| {
-            self.integer(*k);
+            self.locale(*k);
             self.file(v);
         });
     }
 
-    fn category(&mut self, category: Category) {
-        self.integer(category.into());
+    fn locale(&mut self, locale: Locale) {
+        self.integer(locale.into());
+    }
+
+    fn locale_name_map(&mut self, names: &BTreeMap<Locale, Cow<'_, str>>) {
+        // Write map length
+        self.integer(names.len().try_into().unwrap_or(u32::MAX));
+
+        names.iter().for_each(|(k, v)| {
+            self.locale(*k);
+            self.name(v);
+        });
+    }
+
+    fn category(&mut self, category: Category) -> Result {
+        let id = category.into();
+
+        if Category::from(id) != category {
+            return Err(Error::new(ErrorKind::Invalid, self.offset()));
+        }
+
+        self.integer(id);
+
+        Ok(())
     }
 
-    fn category_vector(&mut self, categories: &[Category]) {
+    fn category_vector(&mut self, categories: &[Category]) -> Result {
         // Write vector length
         self.integer(categories.len().try_into().unwrap_or(u32::MAX));
 
         categories
             .iter()
-            .for_each(|category| self.category(*category));
+            .try_for_each(|category| self.category(*category))
     }
 }
@@ -0,0 +1,59 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::Error;
+
+/// Kind of [`Diagnostic`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// Section comes after a section that should come after it (`name`, then
+    /// `producers`, then `daku`)
+    Misordered,
+    /// Section has the same name as an earlier `name`, `producers` or `daku`
+    /// section
+    Duplicate,
+    /// Section failed to parse
+    Malformed(Error),
+}
+
+/// A problem found with one of a module's custom sections
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// What the problem is
+    pub kind: DiagnosticKind,
+    /// Name of the custom section
+    pub name: String,
+    /// Index of the section within the custom sections of the module
+    pub index: usize,
+    /// Byte offset of the section within the module
+    pub offset: usize,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            name,
+            index,
+            offset,
+            ..
+        } = self;
+
+        write!(f, "`{name}` section #{index} at offset {offset:#x}: ")?;
+
+        match &self.kind {
+            DiagnosticKind::Misordered => f.write_str("out of order"),
+            DiagnosticKind::Duplicate => f.write_str("duplicate section"),
+            DiagnosticKind::Malformed(error) => write!(f, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,779 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+//
+//! Semantic diff of app metadata.
+//!
+//! ```rust
+//! use nucleide::{
+//!     daku::{Daku, Portal, PortalSet},
+//!     diff::{self, Change},
+//! };
+//!
+//! let old = Daku {
+//!     portals: PortalSet::from([Portal::Timer]),
+//!     nucleide: None,
+//! };
+//! let new = Daku {
+//!     portals: PortalSet::from([Portal::Timer, Portal::Fetch]),
+//!     nucleide: None,
+//! };
+//! let changes = diff::daku(&old, &new);
+//!
+//! assert_eq!(changes, [Change::PortalAdded(Portal::Fetch)]);
+//! assert!(changes[0].needs_permission());
+//! ```
+
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    daku::{Category, Daku, Nucleide, Portal, PortalSet},
+    locale::Locale,
+    name::Name,
+    producers::{Producer, ProducerKind},
+    Module, Result, Section,
+};
+
+/// A single change to app metadata
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Change {
+    /// Portal was added (the user has to grant a new permission)
+    PortalAdded(Portal),
+    /// Portal was removed
+    PortalRemoved(Portal),
+    /// Localized app name was added, removed or changed
+    Name {
+        /// Locale of the value
+        locale: Locale,
+        /// Previous name
+        old: Option<String>,
+        /// New name
+        new: Option<String>,
+    },
+    /// Localized app description was added, removed or changed
+    Description {
+        /// Locale of the value
+        locale: Locale,
+        /// Previous description
+        old: Option<String>,
+        /// New description
+        new: Option<String>,
+    },
+    /// Icon theme was added, removed or had its files swapped (each entry of a
+    /// repeated theme is compared separately, in order)
+    Icon {
+        /// Theme name
+        theme: String,
+        /// Previous icon file
+        old: Option<FileDigest>,
+        /// New icon file
+        new: Option<FileDigest>,
+    },
+    /// Localized asset was added, removed or changed
+    Asset {
+        /// Locale of the value
+        locale: Locale,
+        /// Previous asset file
+        old: Option<AssetFile>,
+        /// New asset file
+        new: Option<AssetFile>,
+    },
+    /// Searchable tag was added
+    TagAdded(String),
+    /// Searchable tag was removed
+    TagRemoved(String),
+    /// Category was added
+    CategoryAdded(Category),
+    /// Category was removed
+    CategoryRemoved(Category),
+    /// Nucleide subsection from a newer version of the specification was
+    /// added, removed or changed
+    Subsection(u8),
+    /// Developer name was added, removed or changed
+    Developer {
+        /// Previous developer name
+        old: Option<String>,
+        /// New developer name
+        new: Option<String>,
+    },
+    /// Module name was added, removed or changed
+    ModuleName {
+        /// Previous module name
+        old: Option<String>,
+        /// New module name
+        new: Option<String>,
+    },
+    /// Debug names of a kind (such as `"function"`) changed
+    DebugNames(&'static str),
+    /// Producer was added
+    ProducerAdded {
+        /// Producer field
+        kind: ProducerKind<'static>,
+        /// Name of the language or tool
+        name: String,
+        /// Version
+        version: String,
+    },
+    /// Producer was removed
+    ProducerRemoved {
+        /// Producer field
+        kind: ProducerKind<'static>,
+        /// Name of the language or tool
+        name: String,
+        /// Version
+        version: String,
+    },
+    /// Producer version changed
+    ProducerUpdated {
+        /// Producer field
+        kind: ProducerKind<'static>,
+        /// Name of the language or tool
+        name: String,
+        /// Previous version
+        old: String,
+        /// New version
+        new: String,
+    },
+    /// Other custom section was added
+    SectionAdded(String),
+    /// Other custom section was removed
+    SectionRemoved(String),
+    /// Other custom section's contents changed
+    SectionChanged(String),
+}
+
+/// Size and SHA-256 hash of a file, to tell whether it changed
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FileDigest {
+    /// Size in bytes
+    pub size: usize,
+    /// SHA-256 hash of the contents
+    pub sha256: [u8; 32],
+}
+
+impl FileDigest {
+    /// Get the digest of a file's contents.
+    pub fn new(data: &[u8]) -> Self {
+        Self {
+            size: data.len(),
+            sha256: Sha256::digest(data).into(),
+        }
+    }
+}
+
+impl fmt::Display for FileDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes, sha256 ", self.size)?;
+
+        for byte in &self.sha256[..4] {
+            write!(f, "{byte:02x}")?;
+        }
+
+        f.write_str("…")
+    }
+}
+
+/// Path and digest of a localized asset
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AssetFile {
+    /// Path the description uses to refer to the asset
+    pub path: String,
+    /// Size and hash of the contents
+    pub digest: FileDigest,
+}
+
+impl AssetFile {
+    fn new(&(path, data): &(&str, &[u8])) -> Self {
+        Self {
+            path: path.to_string(),
+            digest: FileDigest::new(data),
+        }
+    }
+}
+
+impl fmt::Display for AssetFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.path, self.digest)
+    }
+}
+
+impl Change {
+    /// Returns true if the change requires a new user permission prompt.
+    pub fn needs_permission(&self) -> bool {
+        matches!(self, Self::PortalAdded(_))
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PortalAdded(portal) => {
+                write!(f, "+ portal {portal:?} (needs new permission)")
+            }
+            Self::PortalRemoved(portal) => write!(f, "- portal {portal:?}"),
+            Self::Name { locale, old, new } => {
+                change(f, format_args!("name [{locale}]"), old, new)
+            }
+            Self::Description { locale, old, new } => {
+                change(f, format_args!("description [{locale}]"), old, new)
+            }
+            Self::Icon { theme, old, new } => {
+                file_change(f, format_args!("icon `{theme}`"), old, new)
+            }
+            Self::Asset { locale, old, new } => {
+                file_change(f, format_args!("asset [{locale}]"), old, new)
+            }
+            Self::TagAdded(tag) => write!(f, "+ tag {tag:?}"),
+            Self::TagRemoved(tag) => write!(f, "- tag {tag:?}"),
+            Self::CategoryAdded(category) => {
+                write!(f, "+ category {category:?}")
+            }
+            Self::CategoryRemoved(category) => {
+                write!(f, "- category {category:?}")
+            }
+            Self::Subsection(id) => write!(f, "~ nucleide subsection {id}"),
+            Self::Developer { old, new } => change(f, "developer", old, new),
+            Self::ModuleName { old, new } => change(f, "module name", old, new),
+            Self::DebugNames(kind) => write!(f, "~ {kind} names"),
+            Self::ProducerAdded {
+                kind,
+                name,
+                version,
+            } => write!(f, "+ {} {name} {version}", kind.as_str()),
+            Self::ProducerRemoved {
+                kind,
+                name,
+                version,
+            } => write!(f, "- {} {name} {version}", kind.as_str()),
+            Self::ProducerUpdated {
+                kind,
+                name,
+                old,
+                new,
+            } => write!(f, "~ {} {name} {old} → {new}", kind.as_str()),
+            Self::SectionAdded(name) => write!(f, "+ section `{name}`"),
+            Self::SectionRemoved(name) => write!(f, "- section `{name}`"),
+            Self::SectionChanged(name) => write!(f, "~ section `{name}`"),
+        }
+    }
+}
+
+/// Write an added, removed or changed file.
+fn file_change(
+    f: &mut fmt::Formatter<'_>,
+    what: impl fmt::Display,
+    old: &Option<impl fmt::Display>,
+    new: &Option<impl fmt::Display>,
+) -> fmt::Result {
+    match (old, new) {
+        (None, Some(new)) => write!(f, "+ {what}: {new}"),
+        (Some(old), None) => write!(f, "- {what}: {old}"),
+        (Some(old), Some(new)) => write!(f, "~ {what}: {old} → {new}"),
+        (None, None) => write!(f, "~ {what}"),
+    }
+}
+
+/// Write an added, removed or changed value.
+fn change(
+    f: &mut fmt::Formatter<'_>,
+    what: impl fmt::Display,
+    old: &Option<String>,
+    new: &Option<String>,
+) -> fmt::Result {
+    match (old, new) {
+        (None, Some(new)) => write!(f, "+ {what}: {new:?}"),
+        (Some(old), None) => write!(f, "- {what}: {old:?}"),
+        (Some(old), Some(new)) => write!(f, "~ {what}: {old:?} → {new:?}"),
+        (None, None) => write!(f, "~ {what}"),
+    }
+}
+
+impl Module {
+    /// Diff the metadata of this module against a `new` version of it.
+    ///
+    /// The `name`, `producers` and `daku` sections are compared semantically
+    /// (a missing section counts as empty), and other custom sections by
+    /// their contents.
+    ///
+    /// # Errors
+    /// Returns an error if a `name`, `producers` or `daku` section is
+    /// malformed.
+    pub fn diff(&self, new: &Self) -> Result<Vec<Change>> {
+        let mut changes = names(
+            &self.names()?.unwrap_or_default(),
+            &new.names()?.unwrap_or_default(),
+        );
+        let empty = || Daku {
+            portals: PortalSet::new(),
+            nucleide: None,
+        };
+
+        changes.extend(producers(
+            &self.producers()?.unwrap_or_default(),
+            &new.producers()?.unwrap_or_default(),
+        ));
+        changes.extend(daku(
+            &self.daku()?.unwrap_or_else(empty),
+            &new.daku()?.unwrap_or_else(empty),
+        ));
+
+        let other = |module: &'_ Self| -> BTreeMap<String, Vec<u8>> {
+            module
+                .sections_lenient()
+                .0
+                .filter_map(|section| match section {
+                    Section::Any { name, data }
+                        if !matches!(&*name, "name" | "producers" | "daku") =>
+                    {
+                        Some((name.into_owned(), data.into_owned()))
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        let (old, new) = (other(self), other(new));
+
+        for (name, change) in diff_maps(&old, &new) {
+            changes.push(match change {
+                (None, Some(_)) => Change::SectionAdded(name.clone()),
+                (Some(_), None) => Change::SectionRemoved(name.clone()),
+                _ => Change::SectionChanged(name.clone()),
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Diff two `daku` sections.
+pub fn daku(old: &Daku<'_>, new: &Daku<'_>) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let (old_fields, new_fields) = (Fields::of(old), Fields::of(new));
+
+    for portal in &new.portals.difference(&old.portals) {
+        changes.push(Change::PortalAdded(portal));
+    }
+
+    for portal in &old.portals.difference(&new.portals) {
+        changes.push(Change::PortalRemoved(portal));
+    }
+
+    for (&locale, (old, new)) in diff_maps(&old_fields.names, &new_fields.names)
+    {
+        changes.push(Change::Name {
+            locale,
+            old: old.map(|old| old.to_string()),
+            new: new.map(|new| new.to_string()),
+        });
+    }
+
+    for (&locale, (old, new)) in
+        diff_maps(&old_fields.descriptions, &new_fields.descriptions)
+    {
+        changes.push(Change::Description {
+            locale,
+            old: old.map(|old| old.to_string()),
+            new: new.map(|new| new.to_string()),
+        });
+    }
+
+    for ((theme, _), (old, new)) in
+        diff_maps(&old_fields.icons, &new_fields.icons)
+    {
+        changes.push(Change::Icon {
+            theme: theme.to_string(),
+            old: old.map(|old| FileDigest::new(old)),
+            new: new.map(|new| FileDigest::new(new)),
+        });
+    }
+
+    for (&locale, (old, new)) in
+        diff_maps(&old_fields.assets, &new_fields.assets)
+    {
+        changes.push(Change::Asset {
+            locale,
+            old: old.map(AssetFile::new),
+            new: new.map(AssetFile::new),
+        });
+    }
+
+    for tag in &new_fields.tags {
+        if !old_fields.tags.contains(tag) {
+            changes.push(Change::TagAdded(tag.to_string()));
+        }
+    }
+
+    for tag in &old_fields.tags {
+        if !new_fields.tags.contains(tag) {
+            changes.push(Change::TagRemoved(tag.to_string()));
+        }
+    }
+
+    for category in &new_fields.categories {
+        if !old_fields.categories.contains(category) {
+            changes.push(Change::CategoryAdded(*category));
+        }
+    }
+
+    for category in &old_fields.categories {
+        if !new_fields.categories.contains(category) {
+            changes.push(Change::CategoryRemoved(*category));
+        }
+    }
+
+    for (&id, _) in diff_maps(&old_fields.unknown, &new_fields.unknown) {
+        changes.push(Change::Subsection(id));
+    }
+
+    if old_fields.developer != new_fields.developer {
+        changes.push(Change::Developer {
+            old: old_fields.developer.map(ToString::to_string),
+            new: new_fields.developer.map(ToString::to_string),
+        });
+    }
+
+    changes
+}
+
+/// Diff two `name` sections.
+pub fn names(old: &[Name<'_>], new: &[Name<'_>]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let module = |names: &[Name<'_>]| {
+        names.iter().find_map(|name| match name {
+            Name::Module(name) => Some(name.to_string()),
+            _ => None,
+        })
+    };
+    let (old_module, new_module) = (module(old), module(new));
+
+    if old_module != new_module {
+        changes.push(Change::ModuleName {
+            old: old_module,
+            new: new_module,
+        });
+    }
+
+    for kind in [
+        "function", "local", "label", "type", "table", "memory", "global",
+        "element", "data", "unknown",
+    ] {
+        let of_kind = |name: &&Name<'_>| debug_kind(name) == Some(kind);
+
+        if old.iter().filter(of_kind).ne(new.iter().filter(of_kind)) {
+            changes.push(Change::DebugNames(kind));
+        }
+    }
+
+    changes
+}
+
+/// Diff two `producers` sections.
+pub fn producers(old: &[Producer<'_>], new: &[Producer<'_>]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let list = |producers: &[Producer<'_>]| {
+        let mut map = BTreeMap::new();
+
+        for producer in producers {
+            for software in &producer.list {
+                let kind = producer.kind.clone().into_owned();
+                let key =
+                    (kind.as_str().to_string(), software.name.to_string());
+
+                map.insert(key, (kind, software.version.to_string()));
+            }
+        }
+
+        map
+    };
+    let (old, new) = (list(old), list(new));
+
+    for ((_, name), change) in diff_maps(&old, &new) {
+        let name = name.clone();
+
+        changes.push(match change {
+            (None, Some((kind, version))) => Change::ProducerAdded {
+                kind: kind.clone(),
+                name,
+                version: version.clone(),
+            },
+            (Some((kind, version)), None) => Change::ProducerRemoved {
+                kind: kind.clone(),
+                name,
+                version: version.clone(),
+            },
+            (Some((_, old)), Some((kind, new))) => Change::ProducerUpdated {
+                kind: kind.clone(),
+                name,
+                old: old.clone(),
+                new: new.clone(),
+            },
+            (None, None) => continue,
+        });
+    }
+
+    changes
+}
+
+/// Kind of debug names in a name subsection
+fn debug_kind(name: &Name<'_>) -> Option<&'static str> {
+    Some(match name {
+        Name::Module(_) => return None,
+        Name::Function(_) => "function",
+        Name::Local(_) => "local",
+        Name::Label(_) => "label",
+        Name::Type(_) => "type",
+        Name::Table(_) => "table",
+        Name::Memory(_) => "memory",
+        Name::Global(_) => "global",
+        Name::Element(_) => "element",
+        Name::Data(_) => "data",
+        Name::Unknown { .. } => "unknown",
+    })
+}
+
+/// Nucleide subsections of a `daku` section, by field
+#[derive(Default)]
+struct Fields<'a> {
+    names: BTreeMap<Locale, &'a str>,
+    descriptions: BTreeMap<Locale, &'a str>,
+    /// Icons by theme, and which entry of the theme they are
+    icons: BTreeMap<(&'a str, usize), &'a [u8]>,
+    assets: BTreeMap<Locale, (&'a str, &'a [u8])>,
+    tags: Vec<&'a str>,
+    categories: Vec<Category>,
+    developer: Option<&'a str>,
+    unknown: BTreeMap<u8, &'a [u8]>,
+}
+
+impl<'a> Fields<'a> {
+    fn of(daku: &'a Daku<'_>) -> Self {
+        let mut fields = Self::default();
+        let strings = |map: &'a BTreeMap<Locale, Cow<'_, str>>| {
+            map.iter()
+                .map(|(&locale, text)| (locale, &**text))
+                .collect()
+        };
+
+        for subsection in daku.nucleide.iter().flatten() {
+            match subsection {
+                Nucleide::LocalizedNames(map) => fields.names = strings(map),
+                Nucleide::LocalizedDescriptions(map) => {
+                    fields.descriptions = strings(map)
+                }
+                Nucleide::ThemedIcons(icons) => {
+                    fields.icons.clear();
+
+                    for icon in icons {
+                        let theme = &*icon.path;
+                        let entry = fields
+                            .icons
+                            .keys()
+                            .filter(|(other, _)| *other == theme)
+                            .count();
+
+                        fields.icons.insert((theme, entry), &icon.data);
+                    }
+                }
+                Nucleide::LocalizedAssets(assets) => {
+                    fields.assets = assets
+                        .iter()
+                        .map(|(&locale, file)| {
+                            (locale, (&*file.path, &*file.data))
+                        })
+                        .collect()
+                }
+                Nucleide::Tags(tags) => {
+                    fields.tags = tags.iter().map(|tag| &**tag).collect()
+                }
+                Nucleide::Categories(categories) => {
+                    fields.categories = categories.clone()
+                }
+                Nucleide::Developer(developer) => {
+                    fields.developer = Some(developer)
+                }
+                Nucleide::Unknown { id, data } => {
+                    fields.unknown.insert(*id, data);
+                }
+            }
+        }
+
+        fields
+    }
+}
+
+/// Old and new value of a map entry
+type Entry<'a, V> = (Option<&'a V>, Option<&'a V>);
+
+/// Entries that were added, removed or changed between two maps
+fn diff_maps<'a, K: Ord, V: PartialEq>(
+    old: &'a BTreeMap<K, V>,
+    new: &'a BTreeMap<K, V>,
+) -> Vec<(&'a K, Entry<'a, V>)> {
+    let mut keys: Vec<&K> = old.keys().chain(new.keys()).collect();
+
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .map(|key| (key, (old.get(key), new.get(key))))
+        .filter(|(_, (old, new))| old != new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec};
+
+    use super::*;
+    use crate::{daku::File, module::HEADER, producers::VersionedSoftware};
+
+    #[test]
+    fn modules() {
+        let en_us: Locale = "en-US".parse().unwrap();
+        let producers = |version: &'static str| Producer {
+            kind: ProducerKind::Language,
+            list: vec![VersionedSoftware {
+                name: "Rust".into(),
+                version: version.into(),
+            }],
+        };
+        let icons = |data: &'static [u8]| {
+            Nucleide::ThemedIcons(vec![File {
+                path: "default".into(),
+                data: data.into(),
+            }])
+        };
+        let mut old = Module::new(&HEADER).unwrap();
+        let mut new = Module::new(&HEADER).unwrap();
+
+        old.set_producers(&[producers("1.70.0")]).unwrap();
+        old.set_daku(&Daku {
+            portals: PortalSet::from([Portal::Timer]),
+            nucleide: Some(vec![
+                Nucleide::LocalizedDescriptions(BTreeMap::from([(
+                    en_us,
+                    "Old".into(),
+                )])),
+                icons(b"qoif1"),
+                Nucleide::Tags(vec!["notes".into()]),
+            ]),
+        })
+        .unwrap();
+        old.set_section(Section::Any {
+            name: "foo".into(),
+            data: [1][..].into(),
+        })
+        .unwrap();
+        new.set_producers(&[producers("1.72.0")]).unwrap();
+        new.set_daku(&Daku {
+            portals: PortalSet::from([Portal::Timer, Portal::Fetch]),
+            nucleide: Some(vec![
+                Nucleide::LocalizedDescriptions(BTreeMap::from([(
+                    en_us,
+                    "New".into(),
+                )])),
+                icons(b"qoif2"),
+                Nucleide::Tags(vec!["notes".into(), "text".into()]),
+            ]),
+        })
+        .unwrap();
+
+        let changes = old.diff(&new).unwrap();
+
+        assert_eq!(
+            changes,
+            [
+                Change::ProducerUpdated {
+                    kind: ProducerKind::Language,
+                    name: "Rust".into(),
+                    old: "1.70.0".into(),
+                    new: "1.72.0".into(),
+                },
+                Change::PortalAdded(Portal::Fetch),
+                Change::Description {
+                    locale: en_us,
+                    old: Some("Old".into()),
+                    new: Some("New".into()),
+                },
+                Change::Icon {
+                    theme: "default".into(),
+                    old: Some(FileDigest::new(b"qoif1")),
+                    new: Some(FileDigest::new(b"qoif2")),
+                },
+                Change::TagAdded("text".into()),
+                Change::SectionRemoved("foo".into()),
+            ],
+        );
+        assert_eq!(changes.iter().filter(|c| c.needs_permission()).count(), 1);
+        assert_eq!(
+            format!("{}", changes[2]),
+            "~ description [enUS]: \"Old\" → \"New\"",
+        );
+        assert_eq!(
+            format!("{}", changes[1]),
+            "+ portal Fetch (needs new permission)",
+        );
+        assert_eq!(
+            format!("{}", changes[3]),
+            "~ icon `default`: 5 bytes, sha256 f7a44478… → 5 bytes, sha256 \
+             5a29afe2…",
+        );
+        assert!(new.diff(&new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn files() {
+        let file = |path: &'static str, data: &'static [u8]| File {
+            path: path.into(),
+            data: data.into(),
+        };
+        let daku = |first: &'static [u8], asset: &'static [u8]| Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![
+                Nucleide::ThemedIcons(vec![
+                    file("default", first),
+                    file("default", b"qoif2"),
+                ]),
+                Nucleide::LocalizedAssets(BTreeMap::from([(
+                    Locale::Any,
+                    file("a.qoi", asset),
+                )])),
+            ]),
+        };
+        let changes = super::daku(&daku(b"qoif1", b"a"), &daku(b"qoif3", b"b"));
+
+        assert_eq!(
+            changes,
+            [
+                Change::Icon {
+                    theme: "default".into(),
+                    old: Some(FileDigest::new(b"qoif1")),
+                    new: Some(FileDigest::new(b"qoif3")),
+                },
+                Change::Asset {
+                    locale: Locale::Any,
+                    old: Some(AssetFile::new(&("a.qoi", b"a"))),
+                    new: Some(AssetFile::new(&("a.qoi", b"b"))),
+                },
+            ],
+        );
+        assert_eq!(
+            format!("{}", changes[1]),
+            "~ asset [*]: a.qoi (1 bytes, sha256 ca978112…) → a.qoi (1 bytes, \
+             sha256 3e23e816…)",
+        );
+    }
+}
@@ -7,25 +7,152 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
+use alloc::string::String;
 use core::fmt;
 
-use parity_wasm::elements;
-
 /// Result type alias
 pub type Result<T = (), E = Error> = core::result::Result<T, E>;
 
+/// Kind of [`Error`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Not a WebAssembly module (bad magic number or version)
+    InvalidHeader,
+    /// Input ended before the value was fully read
+    Truncated,
+    /// Malformed LEB128 integer, or one that doesn't fit in 32 bits
+    Leb128,
+    /// A name wasn't valid UTF-8
+    Utf8,
+    /// The `name`, `producers` and `daku` sections are out of order
+    SectionOrder,
+    /// A section that was referred to doesn't exist
+    MissingSection,
+    /// Subsection is out of order, or repeated
+    SubsectionOrder,
+    /// Map indices aren't in strictly increasing order
+    IndexOrder,
+    /// A value didn't match any of the known possibilities
+    UnknownValue {
+        /// What the value was supposed to be (for example, `"portal"`)
+        what: &'static str,
+        /// The unknown value
+        value: u32,
+    },
+    /// Bytes left over after the end of a structure
+    TrailingBytes,
+    /// Data belongs to a different module
+    Mismatch,
+    /// Reading, decompressing or compressing failed
+    Io,
+    /// A length is too large to be encoded
+    Overflow,
+    /// The section isn't one this crate knows how to parse
+    Unsupported,
+    /// Metadata breaks a rule of the specification
+    Invalid,
+    /// None of the module's imports map to a portal
+    NoPortalImports,
+    /// A decompressed module is larger than the limit
+    TooLarge,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => f.write_str("invalid WebAssembly header"),
+            Self::Truncated => f.write_str("unexpected end of input"),
+            Self::Leb128 => f.write_str("invalid LEB128 integer"),
+            Self::Utf8 => f.write_str("invalid UTF-8"),
+            Self::SectionOrder => f.write_str("incorrect section order"),
+            Self::MissingSection => f.write_str("missing section"),
+            Self::SubsectionOrder => f.write_str("incorrect subsection order"),
+            Self::IndexOrder => f.write_str("indices out of order"),
+            Self::UnknownValue { what, value } => {
+                write!(f, "unknown {what} {value}")
+            }
+            Self::TrailingBytes => f.write_str("trailing bytes"),
+            Self::Mismatch => f.write_str("belongs to a different module"),
+            Self::Io => f.write_str("I/O error"),
+            Self::Overflow => f.write_str("length too large to encode"),
+            Self::Unsupported => f.write_str("unsupported section"),
+            Self::Invalid => f.write_str("metadata breaks the specification"),
+            Self::NoPortalImports => f.write_str("no imports map to a portal"),
+            Self::TooLarge => f.write_str("decompressed module is too large"),
+        }
+    }
+}
+
 /// Deserialization/serialization error
-#[derive(Debug)]
-pub struct Error(pub(crate) elements::Error);
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: usize,
+    section: Option<String>,
+    subsection: Option<u8>,
+}
 
 impl Error {
-    pub(crate) const fn with_msg(message: &'static str) -> Self {
-        Self(elements::Error::Other(message))
+    /// Create a new error of `kind` at byte `offset`.
+    pub(crate) const fn new(kind: ErrorKind, offset: usize) -> Self {
+        Self {
+            kind,
+            offset,
+            section: None,
+            subsection: None,
+        }
+    }
+
+    /// Set the custom section the error happened in, if not already set.
+    pub(crate) fn in_section(mut self, name: &str) -> Self {
+        self.section.get_or_insert_with(|| name.into());
+        self
+    }
+
+    /// Set the subsection the error happened in, if not already set.
+    pub(crate) fn in_subsection(mut self, id: u8) -> Self {
+        self.subsection.get_or_insert(id);
+        self
+    }
+
+    /// Get the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Get the byte offset where the error happened.
+    ///
+    /// Errors from [`Module`](crate::Module) are relative to the start of the
+    /// module; errors from parsing a [`Section`](crate::Section) are relative
+    /// to the start of its payload; and write errors are relative to the start
+    /// of the output.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Get the name of the custom section the error happened in, if known.
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    /// Get the ID of the subsection the error happened in, if any.
+    pub fn subsection(&self) -> Option<u8> {
+        self.subsection
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        <elements::Error as fmt::Display>::fmt(&self.0, f)
+        write!(f, "{} at offset {:#x}", self.kind, self.offset)?;
+
+        match (&self.section, self.subsection) {
+            (Some(section), Some(id)) => {
+                write!(f, " (`{section}` section, subsection {id})")
+            }
+            (Some(section), None) => write!(f, " (`{section}` section)"),
+            (None, Some(id)) => write!(f, " (subsection {id})"),
+            (None, None) => Ok(()),
+        }
     }
 }
@@ -31,19 +31,39 @@
 )]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+#[cfg(feature = "zstd")]
+mod container;
 pub mod daku;
+mod diagnostic;
+pub mod diff;
 mod error;
+mod locale;
+#[cfg(feature = "manifest")]
+pub mod manifest;
 mod module;
 pub mod name;
 pub mod parse;
+mod placement;
 pub mod producers;
+#[cfg(feature = "serde")]
+mod report;
 mod seal;
 mod section;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sidecar;
 pub mod wasm;
 
+#[cfg(feature = "serde")]
+pub use self::report::{Report, SectionReport};
 pub use self::{
-    error::{Error, Result},
+    diagnostic::{Diagnostic, DiagnosticKind},
+    error::{Error, ErrorKind, Result},
+    locale::{Locale, ParseLocaleError},
     module::Module,
+    placement::{Placement, SectionId},
     section::Section,
 };
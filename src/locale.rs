@@ -0,0 +1,195 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use core::{cmp::Ordering, fmt, str::FromStr};
+
+/// Locale of localized metadata (Nucleide extension)
+///
+/// Encoded as a 4-letter ASCII description packed into an integer, 7 bits
+/// per letter (`locale[0] | locale[1] << 7 | locale[2] << 14 |
+/// locale[3] << 21`), or `0` for non-localized values.
+///
+/// ```rust
+/// use nucleide::Locale;
+///
+/// let locale: Locale = "en-US".parse().unwrap();
+///
+/// assert_eq!(locale, "enUS".parse().unwrap());
+/// assert_eq!(locale.to_string(), "enUS");
+/// assert_eq!(format!("{locale:#}"), "en-US");
+/// assert_eq!(Locale::from_u32(u32::from(locale)), Some(locale));
+/// assert!("EN-us".parse::<Locale>().is_err());
+/// ```
+///
+/// Locales are ordered by their packed integer, which is the order they are
+/// encoded in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Locale {
+    /// Not localized (encoded as `0`, written as `*`)
+    Any,
+    /// Language and region, such as `enUS`
+    Code {
+        /// Two-letter lowercase ASCII language code (such as `*b"en"`)
+        language: [u8; 2],
+        /// Two-letter uppercase ASCII region code (such as `*b"US"`)
+        region: [u8; 2],
+    },
+}
+
+/// Error parsing a [`Locale`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ParseLocaleError;
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid locale (expected `*`, or one like `en-US`)")
+    }
+}
+
+impl Locale {
+    /// Create a locale from a language and region code, if they are valid.
+    pub fn new(language: [u8; 2], region: [u8; 2]) -> Option<Self> {
+        let valid = language.iter().all(u8::is_ascii_lowercase)
+            && region.iter().all(u8::is_ascii_uppercase);
+
+        valid.then_some(Self::Code { language, region })
+    }
+
+    /// Unpack a locale from its integer encoding, if it is valid.
+    pub fn from_u32(packed: u32) -> Option<Self> {
+        if packed == 0 {
+            return Some(Self::Any);
+        }
+
+        if packed >> 28 != 0 {
+            return None;
+        }
+
+        let [l0, l1, r0, r1] =
+            [0, 7, 14, 21].map(|shift| (packed >> shift & 0x7F) as u8);
+
+        Self::new([l0, l1], [r0, r1])
+    }
+
+    /// Pack the locale into its integer encoding.
+    pub fn to_u32(self) -> u32 {
+        match self {
+            Self::Any => 0,
+            Self::Code { language, region } => {
+                let letters = [language[0], language[1], region[0], region[1]];
+
+                letters
+                    .iter()
+                    .zip([0, 7, 14, 21])
+                    .fold(0, |packed, (&c, s)| {
+                        packed | u32::from(c & 0x7F) << s
+                    })
+            }
+        }
+    }
+
+    /// Get the language code (such as `"en"`), unless not localized.
+    pub fn language(&self) -> Option<&str> {
+        match self {
+            Self::Any => None,
+            Self::Code { language, .. } => core::str::from_utf8(language).ok(),
+        }
+    }
+
+    /// Get the region code (such as `"US"`), unless not localized.
+    pub fn region(&self) -> Option<&str> {
+        match self {
+            Self::Any => None,
+            Self::Code { region, .. } => core::str::from_utf8(region).ok(),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    /// Parse a locale written as `en-US` or `enUS`, or `*` for non-localized.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        if string == "*" {
+            return Ok(Self::Any);
+        }
+
+        let bytes = string.as_bytes();
+        let (language, region) = match bytes {
+            [l0, l1, b'-', r0, r1] | [l0, l1, r0, r1] => {
+                ([*l0, *l1], [*r0, *r1])
+            }
+            _ => return Err(ParseLocaleError),
+        };
+
+        Self::new(language, region).ok_or(ParseLocaleError)
+    }
+}
+
+impl fmt::Display for Locale {
+    /// Formats as `enUS`, or `en-US` with the alternate flag (`{:#}`), and `*`
+    /// if not localized.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Some(language), Some(region)) = (self.language(), self.region())
+        else {
+            return f.write_str("*");
+        };
+
+        if f.alternate() {
+            write!(f, "{language}-{region}")
+        } else {
+            write!(f, "{language}{region}")
+        }
+    }
+}
+
+impl Ord for Locale {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_u32().cmp(&other.to_u32())
+    }
+}
+
+impl PartialOrd for Locale {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Locale> for u32 {
+    fn from(locale: Locale) -> Self {
+        locale.to_u32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale() {
+        let en_us = 0x65 | 0x6E << 7 | 0x55 << 14 | 0x53 << 21;
+        let locale = Locale::from_u32(en_us).unwrap();
+
+        assert_eq!(locale.to_u32(), en_us);
+        assert_eq!(locale.language(), Some("en"));
+        assert_eq!(locale.region(), Some("US"));
+        assert_eq!("*".parse(), Ok(Locale::Any));
+        assert_eq!(Locale::from_u32(0), Some(Locale::Any));
+        assert_eq!(Locale::from_u32(1), None);
+        assert_eq!(Locale::from_u32(en_us | 1 << 28), None);
+
+        for invalid in ["", "en", "en_US", "enus", "ENUS", "én-US", "en-USA"] {
+            assert_eq!(invalid.parse::<Locale>(), Err(ParseLocaleError));
+        }
+
+        // Ordered by packed integer, not alphabetically
+        assert!(Locale::Any < locale);
+        assert!("zzAA".parse::<Locale>().unwrap() < locale);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    string::{String, ToString},
+};
+
+/// Manifest error, with the location in the manifest it happened at
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    path: PathBuf,
+    key: Option<String>,
+    line: Option<usize>,
+    message: String,
+}
+
+impl Error {
+    pub(crate) fn new(path: &Path, message: impl fmt::Display) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            key: None,
+            line: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// Set the key path (if not already set).
+    pub(crate) fn at_key(mut self, key: impl fmt::Display) -> Self {
+        let key = key.to_string();
+
+        if self.key.is_none() && key != "." {
+            self.key = Some(key);
+        }
+
+        self
+    }
+
+    /// Set the line number (if not already set).
+    pub(crate) fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = self.line.or(line);
+        self
+    }
+
+    /// Get the path of the manifest.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the dotted key path within the manifest (such as `icons.default`),
+    /// if known.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Get the line number within the manifest (starting at 1), if known.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Get the description of what went wrong.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+
+        if let Some(key) = &self.key {
+            write!(f, ": `{key}`")?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,393 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{
+    collections::BTreeSet,
+    format, fs,
+    path::{Component, Path, PathBuf},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{Asset, Error, Manifest, RawSubsection, Result, Text};
+use crate::{
+    daku::{File, Nucleide},
+    locale::Locale,
+    name::Name,
+    Module,
+};
+
+impl Manifest {
+    /// Generate a manifest from the `daku`, `name` and `producers` sections of
+    /// a module.
+    ///
+    /// Icons and assets are written out to the `icons` and `assets`
+    /// directories within `dir`, which is where the manifest is expected to
+    /// be saved.  Files whose names would collide (ignoring case) get a
+    /// numbered suffix, such as `default-2.qoi`.
+    ///
+    /// # Errors
+    /// Returns an error if an icon theme is repeated, since a manifest can
+    /// only list each theme once.  This is checked before any files are
+    /// written.
+    pub fn export(module: &Module, dir: &Path) -> Result<Self> {
+        let error = |e| Error::new(dir, e);
+        let mut manifest = Self::default();
+        let mut files = BTreeSet::new();
+
+        if let Some(names) = module.names().map_err(error)? {
+            manifest.module = names.into_iter().find_map(|name| match name {
+                Name::Module(name) => Some(name.into_owned()),
+                _ => None,
+            });
+        }
+
+        if let Some(producers) = module.producers().map_err(error)? {
+            manifest.producers =
+                producers.into_iter().map(|p| p.into_owned()).collect();
+        }
+
+        let Some(daku) = module.daku().map_err(error)? else {
+            return Ok(manifest);
+        };
+
+        // Check before writing any files, so a failed export leaves nothing
+        // behind
+        let mut themes = BTreeSet::new();
+
+        for subsection in daku.nucleide.iter().flatten() {
+            let Nucleide::ThemedIcons(icons) = subsection else {
+                continue;
+            };
+
+            for File { path, .. } in icons {
+                if !themes.insert(&**path) {
+                    return Err(Error::new(
+                        dir,
+                        format_args!("icon theme `{path}` is repeated"),
+                    ));
+                }
+            }
+        }
+
+        manifest.portals = daku.portals;
+
+        for subsection in daku.nucleide.into_iter().flatten() {
+            match subsection {
+                Nucleide::LocalizedNames(names) => {
+                    manifest.name = names
+                        .into_iter()
+                        .map(|(locale, name)| (locale, name.into_owned()))
+                        .collect();
+                }
+                Nucleide::LocalizedDescriptions(descriptions) => {
+                    manifest.description = descriptions
+                        .into_iter()
+                        .map(|(locale, text)| {
+                            (locale, Text::Inline(text.into_owned()))
+                        })
+                        .collect();
+                }
+                Nucleide::ThemedIcons(icons) => {
+                    for File { path, data } in icons {
+                        let file = Path::new("icons")
+                            .join(sanitize(&path))
+                            .with_extension("qoi");
+                        let file = unique(&mut files, file);
+
+                        write(dir, &file, &data)?;
+                        manifest.icons.insert(path.into_owned(), [file].into());
+                    }
+                }
+                Nucleide::LocalizedAssets(assets) => {
+                    for (locale, File { path, data }) in assets {
+                        // `*` isn't allowed in file names on every platform
+                        let locale_dir = match locale {
+                            Locale::Any => "any".to_string(),
+                            Locale::Code { .. } => locale.to_string(),
+                        };
+                        let file = Path::new("assets")
+                            .join(locale_dir)
+                            .join(sanitize(&path));
+                        let file = unique(&mut files, file);
+
+                        write(dir, &file, &data)?;
+                        manifest.assets.insert(
+                            locale,
+                            Asset {
+                                path: path.into_owned(),
+                                file: Some(file),
+                            },
+                        );
+                    }
+                }
+                Nucleide::Tags(tags) => {
+                    manifest.tags =
+                        tags.into_iter().map(|tag| tag.into_owned()).collect();
+                }
+                Nucleide::Categories(categories) => {
+                    manifest.categories = categories;
+                }
+                Nucleide::Developer(developer) => {
+                    manifest.developer = Some(developer.into_owned());
+                }
+                Nucleide::Unknown { id, data } => {
+                    manifest.unknown.push(RawSubsection {
+                        id,
+                        data: data.into_owned().into(),
+                    });
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Write the manifest to a TOML file, or a JSON file if the extension is
+    /// `.json`.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result {
+        let path = path.as_ref();
+        let json = path.extension().is_some_and(|ext| ext == "json");
+        let text = if json {
+            serde_json::to_string_pretty(self)
+                .map_err(|e| Error::new(path, e))?
+        } else {
+            toml::to_string_pretty(self).map_err(|e| Error::new(path, e))?
+        };
+
+        fs::write(path, text).map_err(|e| Error::new(path, e))
+    }
+
+    /// Embed the manifest at `path` into a module, replacing its `daku`
+    /// section.
+    ///
+    /// The module name and `producers` section are only replaced if they are
+    /// set in the manifest.  Files are read relative to the directory
+    /// containing `path`.
+    pub fn embed(&self, module: &mut Module, path: &Path) -> Result {
+        let error = |e| Error::new(path, e);
+
+        module.set_daku(&self.to_daku(path)?).map_err(error)?;
+
+        if let Some(name) = &self.module {
+            let mut names: Vec<Name<'static>> = module
+                .names()
+                .map_err(error)?
+                .into_iter()
+                .flatten()
+                .filter(|name| !matches!(name, Name::Module(_)))
+                .map(Name::into_owned)
+                .collect();
+
+            names.insert(0, Name::Module(name.clone().into()));
+            module.set_names(&names).map_err(error)?;
+        }
+
+        if !self.producers.is_empty() {
+            module.set_producers(&self.producers).map_err(error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Turn a path stored in a module into a relative path that can't escape the
+/// directory it's joined to.
+fn sanitize(path: &str) -> PathBuf {
+    let path: PathBuf = Path::new(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect();
+
+    if path.as_os_str().is_empty() {
+        PathBuf::from("file")
+    } else {
+        path
+    }
+}
+
+/// Add a numbered suffix to `file` if needed, so that it doesn't collide with
+/// any of the `used` files (ignoring case, as some file systems do).
+fn unique(used: &mut BTreeSet<String>, file: PathBuf) -> PathBuf {
+    let key = |file: &Path| file.to_string_lossy().to_lowercase();
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let mut unique = file.clone();
+
+    for n in 2.. {
+        if used.insert(key(&unique)) {
+            break;
+        }
+
+        let name = match file.extension() {
+            Some(ext) => format!("{stem}-{n}.{}", ext.to_string_lossy()),
+            None => format!("{stem}-{n}"),
+        };
+
+        unique = file.with_file_name(name);
+    }
+
+    unique
+}
+
+/// Write `data` to `file` within `dir`, creating directories as needed.
+fn write(dir: &Path, file: &Path, data: &[u8]) -> Result {
+    let path = dir.join(file);
+    let error = |e| Error::new(dir, format_args!("{}: {e}", file.display()));
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(error)?;
+    }
+
+    fs::write(path, data).map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, env, format, process, vec};
+
+    use super::*;
+    use crate::{
+        daku::{Daku, Portal, PortalSet},
+        module::HEADER,
+        producers::{Producer, ProducerKind, VersionedSoftware},
+    };
+
+    #[test]
+    fn round_trip() {
+        let dir =
+            env::temp_dir().join(format!("nucleide-export-{}", process::id()));
+        let mut module = Module::new(&HEADER).unwrap();
+        let daku = Daku {
+            portals: PortalSet::from([Portal::Timer]),
+            nucleide: Some(vec![
+                Nucleide::LocalizedNames(BTreeMap::from([(
+                    Locale::Any,
+                    "App".into(),
+                )])),
+                Nucleide::LocalizedDescriptions(BTreeMap::from([(
+                    Locale::Any,
+                    "# App\n\nDoes things.\n".into(),
+                )])),
+                Nucleide::ThemedIcons(vec![File {
+                    path: "default".into(),
+                    data: [1, 2, 3][..].into(),
+                }]),
+                Nucleide::LocalizedAssets(BTreeMap::from([(
+                    Locale::Any,
+                    File {
+                        path: "../../shot.qoi".into(),
+                        data: [4][..].into(),
+                    },
+                )])),
+                Nucleide::Tags(vec!["utility".into()]),
+                Nucleide::Unknown {
+                    id: 9,
+                    data: [0xDE, 0xAD][..].into(),
+                },
+            ]),
+        };
+        let producers = [Producer {
+            kind: ProducerKind::Language,
+            list: vec![VersionedSoftware {
+                name: "Rust".into(),
+                version: "1.70.0".into(),
+            }],
+        }];
+
+        module.set_daku(&daku).unwrap();
+        module.set_producers(&producers).unwrap();
+        module
+            .set_names(&[
+                Name::Module("app".into()),
+                Name::Function(BTreeMap::from([(0, "main".into())])),
+            ])
+            .unwrap();
+
+        let manifest = Manifest::export(&module, &dir).unwrap();
+        let path = dir.join("nucleide.toml");
+
+        assert_eq!(manifest.module.as_deref(), Some("app"));
+        assert!(dir.join("icons/default.qoi").exists());
+        assert!(dir.join("assets/any/shot.qoi").exists());
+
+        manifest.write(&path).unwrap();
+
+        let manifest = Manifest::read(&path).unwrap();
+        let mut copy = Module::new(&HEADER).unwrap();
+
+        copy.set_names(&[Name::Function(BTreeMap::from([(0, "main".into())]))])
+            .unwrap();
+        manifest.embed(&mut copy, &path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(copy.daku().unwrap().unwrap(), daku);
+        assert_eq!(copy.producers().unwrap().unwrap(), producers);
+        assert_eq!(copy.names().unwrap(), module.names().unwrap());
+    }
+
+    #[test]
+    fn collisions() {
+        let dir = env::temp_dir()
+            .join(format!("nucleide-collisions-{}", process::id()));
+        let icon = |path: &'static str, data: &'static [u8]| File {
+            path: path.into(),
+            data: data.into(),
+        };
+        let mut daku = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::ThemedIcons(vec![
+                icon("default", &[1]),
+                icon("Default", &[2]),
+                icon("../default", &[3]),
+            ])]),
+        };
+        let mut module = Module::new(&HEADER).unwrap();
+
+        module.set_daku(&daku).unwrap();
+
+        let manifest = Manifest::export(&module, &dir).unwrap();
+        let files: Vec<_> = manifest.icons.values().flatten().collect();
+
+        assert_eq!(
+            files,
+            [
+                Path::new("icons/default-3.qoi"),
+                Path::new("icons/Default-2.qoi"),
+                Path::new("icons/default.qoi"),
+            ],
+        );
+        assert_eq!(fs::read(dir.join(files[1])).unwrap(), [2]);
+        assert_eq!(
+            manifest
+                .to_daku(&dir.join("nucleide.toml"))
+                .unwrap()
+                .nucleide,
+            Some(vec![Nucleide::ThemedIcons(vec![
+                icon("../default", &[3]),
+                icon("Default", &[2]),
+                icon("default", &[1]),
+            ])]),
+        );
+
+        daku.nucleide = Some(vec![Nucleide::ThemedIcons(vec![
+            icon("default", &[1]),
+            icon("default", &[2]),
+        ])]);
+        module.set_daku(&daku).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let error = Manifest::export(&module, &dir).unwrap_err();
+
+        assert_eq!(error.message(), "icon theme `default` is repeated");
+        assert!(!dir.exists());
+    }
+}
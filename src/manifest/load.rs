@@ -0,0 +1,366 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{Error, Manifest, RawSubsection, Result, Text};
+use crate::daku::{Daku, File, Nucleide};
+
+impl Manifest {
+    /// Read a manifest from a TOML file, or a JSON file if the extension is
+    /// `.json`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::new(path, e))?;
+        let json = path.extension().is_some_and(|ext| ext == "json");
+
+        if json {
+            Self::from_json(&text, path)
+        } else {
+            Self::from_toml(&text, path)
+        }
+    }
+
+    /// Parse a TOML manifest.
+    ///
+    /// `path` is only used for error reporting.
+    pub fn from_toml(text: &str, path: &Path) -> Result<Self> {
+        let deserializer = toml::Deserializer::new(text);
+
+        let manifest: Self = serde_path_to_error::deserialize(deserializer)
+            .map_err(|e| {
+                let key = e.path().to_string();
+                let e = e.into_inner();
+                let line = e
+                    .span()
+                    .map(|span| text[..span.start].matches('\n').count() + 1);
+
+                Error::new(path, e.message()).at_key(key).at_line(line)
+            })?;
+
+        manifest.check_unknown(path)?;
+
+        Ok(manifest)
+    }
+
+    /// Parse a JSON manifest.
+    ///
+    /// `path` is only used for error reporting.
+    pub fn from_json(text: &str, path: &Path) -> Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let manifest: Self = serde_path_to_error::deserialize(
+            &mut deserializer,
+        )
+        .map_err(|e| {
+            let key = e.path().to_string();
+            let e = e.into_inner();
+
+            Error::new(path, &e).at_key(key).at_line(Some(e.line()))
+        })?;
+
+        deserializer
+            .end()
+            .map_err(|e| Error::new(path, &e).at_line(Some(e.line())))?;
+        manifest.check_unknown(path)?;
+
+        Ok(manifest)
+    }
+
+    /// Build the `daku` section described by the manifest at `path`.
+    ///
+    /// Files are read relative to the directory containing `path`.
+    pub fn to_daku(&self, path: &Path) -> Result<Daku<'static>> {
+        self.check_unknown(path)?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let read = |file: &Path, key: &dyn fmt::Display| {
+            fs::read(dir.join(file)).map_err(|e| {
+                Error::new(path, format_args!("{}: {e}", file.display()))
+                    .at_key(key)
+            })
+        };
+        let mut nucleide = Vec::new();
+
+        if !self.name.is_empty() {
+            nucleide.push(Nucleide::LocalizedNames(
+                self.name
+                    .iter()
+                    .map(|(&locale, name)| (locale, name.clone().into()))
+                    .collect(),
+            ));
+        }
+
+        if !self.description.is_empty() {
+            let mut descriptions = BTreeMap::new();
+
+            for (&locale, text) in &self.description {
+                let text = match text {
+                    Text::Inline(text) => text.clone(),
+                    Text::File { file } => {
+                        let key =
+                            format_args!("description.{locale}").to_string();
+
+                        String::from_utf8(read(file, &key)?).map_err(|_| {
+                            Error::new(path, "file is not valid UTF-8")
+                                .at_key(key)
+                        })?
+                    }
+                };
+
+                descriptions.insert(locale, text.into());
+            }
+
+            nucleide.push(Nucleide::LocalizedDescriptions(descriptions));
+        }
+
+        if !self.icons.is_empty() {
+            let mut icons = Vec::new();
+
+            for (theme, files) in &self.icons {
+                let mut data = Vec::new();
+
+                for (i, file) in files.iter().enumerate() {
+                    let key = format_args!("icons.{theme}[{i}]").to_string();
+
+                    data.extend(read(file, &key)?);
+                }
+
+                icons.push(File {
+                    path: theme.clone().into(),
+                    data: data.into(),
+                });
+            }
+
+            nucleide.push(Nucleide::ThemedIcons(icons));
+        }
+
+        if !self.assets.is_empty() {
+            let mut assets = BTreeMap::new();
+
+            for (&locale, asset) in &self.assets {
+                let file = asset
+                    .file
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new(&asset.path));
+                let key = format_args!("assets.{locale}").to_string();
+                let file = File {
+                    path: asset.path.clone().into(),
+                    data: Cow::Owned(read(file, &key)?),
+                };
+
+                assets.insert(locale, file);
+            }
+
+            nucleide.push(Nucleide::LocalizedAssets(assets));
+        }
+
+        if !self.tags.is_empty() {
+            nucleide.push(Nucleide::Tags(
+                self.tags.iter().map(|tag| tag.clone().into()).collect(),
+            ));
+        }
+
+        if !self.categories.is_empty() {
+            nucleide.push(Nucleide::Categories(self.categories.clone()));
+        }
+
+        if let Some(developer) = &self.developer {
+            nucleide.push(Nucleide::Developer(developer.clone().into()));
+        }
+
+        for RawSubsection { id, data } in &self.unknown {
+            nucleide.push(Nucleide::Unknown {
+                id: *id,
+                data: data.clone(),
+            });
+        }
+
+        nucleide.sort_by_key(Nucleide::id);
+
+        Ok(Daku {
+            portals: self.portals.clone(),
+            nucleide: (!nucleide.is_empty()).then_some(nucleide),
+        })
+    }
+
+    /// Check that the `unknown` subsections don't use a reserved or known
+    /// subsection ID, or repeat an ID, since they couldn't be written.
+    fn check_unknown(&self, path: &Path) -> Result {
+        let mut ids = BTreeSet::new();
+
+        for (i, RawSubsection { id, .. }) in self.unknown.iter().enumerate() {
+            let problem = match id {
+                0 => "is reserved",
+                1..=7 => "is used by a known subsection",
+                _ if !ids.insert(id) => "is repeated",
+                _ => continue,
+            };
+
+            return Err(Error::new(
+                path,
+                format_args!("subsection ID {id} {problem}"),
+            )
+            .at_key(format_args!("unknown[{i}].id")));
+        }
+
+        Ok(())
+    }
+}
+
+/// Compile the manifest at `path` into a `daku` section.
+pub fn load(path: impl AsRef<Path>) -> Result<Daku<'static>> {
+    let path = path.as_ref();
+
+    Manifest::read(path)?.to_daku(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, format, process, vec};
+
+    use super::*;
+    use crate::{
+        daku::{Category, Portal, PortalSet},
+        locale::Locale,
+    };
+
+    const MANIFEST: &str = r#"
+portals = ["Fetch"]
+categories = ["Office"]
+developer = "Ardaku"
+
+[name]
+"*" = "Notes"
+de-DE = "Notizen"
+
+[description]
+enUS = { file = "README.md" }
+
+[icons]
+default = ["a.qoi", "b.qoi"]
+
+[assets]
+"*" = { path = "shot.qoi" }
+"#;
+
+    #[test]
+    fn compile() {
+        let dir = env::temp_dir().join(format!("nucleide-{}", process::id()));
+        let path = dir.join("nucleide.toml");
+        let de_de = "de-DE".parse().unwrap();
+        let en_us = "en-US".parse().unwrap();
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, MANIFEST).unwrap();
+        fs::write(dir.join("README.md"), "# Notes").unwrap();
+        fs::write(dir.join("a.qoi"), [1, 2]).unwrap();
+        fs::write(dir.join("b.qoi"), [3]).unwrap();
+
+        let error = load(&path).unwrap_err();
+
+        assert_eq!(error.key(), Some("assets.*"));
+        assert!(error.message().starts_with("shot.qoi: "));
+
+        fs::write(dir.join("shot.qoi"), [4]).unwrap();
+
+        let daku = load(&path).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            daku,
+            Daku {
+                portals: PortalSet::from([Portal::Fetch]),
+                nucleide: Some(vec![
+                    Nucleide::LocalizedNames(BTreeMap::from([
+                        (Locale::Any, "Notes".into()),
+                        (de_de, "Notizen".into()),
+                    ])),
+                    Nucleide::LocalizedDescriptions(BTreeMap::from([(
+                        en_us,
+                        "# Notes".into(),
+                    )])),
+                    Nucleide::ThemedIcons(vec![File {
+                        path: "default".into(),
+                        data: [1, 2, 3][..].into(),
+                    }]),
+                    Nucleide::LocalizedAssets(BTreeMap::from([(
+                        Locale::Any,
+                        File {
+                            path: "shot.qoi".into(),
+                            data: [4][..].into(),
+                        },
+                    )])),
+                    Nucleide::Categories(vec![Category::Office]),
+                    Nucleide::Developer("Ardaku".into()),
+                ]),
+            },
+        );
+    }
+
+    #[test]
+    fn error_location() {
+        let path = Path::new("nucleide.toml");
+        let error =
+            Manifest::from_toml("tags = []\n\n[name]\nenglish = \"\"\n", path)
+                .unwrap_err();
+
+        assert_eq!(error.key(), Some("name.english"));
+        assert_eq!(error.line(), Some(4));
+
+        let path = Path::new("nucleide.json");
+        let error =
+            Manifest::from_json("{\n  \"categories\": [\"Food\"]\n}", path)
+                .unwrap_err();
+
+        assert_eq!(error.key(), Some("categories[0]"));
+        assert_eq!(error.line(), Some(2));
+        assert!(error.to_string().starts_with("nucleide.json:2: "));
+    }
+
+    #[test]
+    fn unknown_ids() {
+        let path = Path::new("nucleide.toml");
+        let unknown = |ids: &[u8]| {
+            ids.iter()
+                .map(|id| format!("[[unknown]]\nid = {id}\ndata = \"\"\n"))
+                .collect::<String>()
+        };
+        let error = Manifest::from_toml(&unknown(&[9, 6]), path).unwrap_err();
+
+        assert_eq!(error.key(), Some("unknown[1].id"));
+        assert_eq!(
+            error.message(),
+            "subsection ID 6 is used by a known subsection"
+        );
+        assert!(error.to_string().starts_with("nucleide.toml: "));
+
+        let error = Manifest::from_toml(&unknown(&[0]), path).unwrap_err();
+
+        assert_eq!(error.message(), "subsection ID 0 is reserved");
+
+        let error = Manifest::from_toml(&unknown(&[9, 9]), path).unwrap_err();
+
+        assert_eq!(error.message(), "subsection ID 9 is repeated");
+
+        let mut manifest = Manifest::from_toml(&unknown(&[9]), path).unwrap();
+
+        manifest.unknown[0].id = 3;
+
+        let error = manifest.to_daku(path).unwrap_err();
+
+        assert_eq!(error.key(), Some("unknown[0].id"));
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+//
+//! Declarative app manifests (`manifest` feature).
+//!
+//! A manifest describes everything that goes into a `daku` section, and is
+//! usually kept in a `nucleide.toml` next to the app's `Cargo.toml`:
+//!
+//! ```toml
+//! portals = ["Fetch", "Timer"]
+//! tags = ["text editor"]
+//! categories = ["Office"]
+//! developer = "Ardaku"
+//!
+//! [name]
+//! "*" = "Notes"
+//! en-US = "Notes"
+//! de-DE = "Notizen"
+//!
+//! [description]
+//! en-US = { file = "README.md" }
+//!
+//! [icons]
+//! default = ["icons/64.qoi", "icons/128.qoi"]
+//! reduced = ["icons/reduced.qoi"]
+//!
+//! [assets]
+//! en-US = { path = "screenshot.qoi", file = "assets/en-US.qoi" }
+//! ```
+//!
+//! The module name and `producers` section can also be set:
+//!
+//! ```toml
+//! module = "notes"
+//!
+//! [[producers]]
+//! kind = "language"
+//! list = [{ name = "Rust", version = "1.70.0" }]
+//! ```
+//!
+//! Locales are written as `en-US` (or `enUS`), with `"*"` for non-localized
+//! values.  File paths are relative to the directory containing the
+//! manifest.  Manifests with a `.json` extension are read and written as JSON,
+//! anything else as TOML.
+//!
+//! Nucleide subsections that aren't recognized are exported as `[[unknown]]`
+//! tables with an `id`, and their `data` in base64, so embedding the manifest
+//! again keeps them.  Their IDs must be unique, and can't be `0` (reserved)
+//! or the ID of a recognized subsection.
+
+mod error;
+mod export;
+mod load;
+
+use std::{
+    borrow::Cow, collections::BTreeMap, path::PathBuf, string::String, vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+pub use self::{error::Error, load::load};
+use crate::{
+    daku::{Category, PortalSet},
+    locale::Locale,
+    producers::Producer,
+};
+
+/// Result type alias for manifests
+pub type Result<T = (), E = Error> = core::result::Result<T, E>;
+
+/// App manifest
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Manifest {
+    /// Portals required by the app
+    #[serde(skip_serializing_if = "PortalSet::is_empty")]
+    pub portals: PortalSet,
+    /// Searchable tags
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// App categories
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<Category>,
+    /// Name of organization/company/developer of the app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub developer: Option<String>,
+    /// Localized app names
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub name: BTreeMap<Locale, String>,
+    /// Localized app descriptions (markdown)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub description: BTreeMap<Locale, Text>,
+    /// Icon files for each theme (`default` and `reduced`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub icons: BTreeMap<String, Vec<PathBuf>>,
+    /// Localized assets for the app description
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub assets: BTreeMap<Locale, Asset>,
+    /// Module name (`name` section)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// Languages and tools used to produce the module (`producers` section)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub producers: Vec<Producer<'static>>,
+    /// Nucleide subsections this crate doesn't recognize, kept so that they
+    /// aren't lost when a manifest is exported and embedded again
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<RawSubsection>,
+}
+
+/// Text that is either written inline or read from a file
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Text {
+    /// Text written directly in the manifest
+    Inline(String),
+    /// Text read from a file
+    File {
+        /// Path to the file
+        file: PathBuf,
+    },
+}
+
+/// Nucleide subsection that isn't recognized, as raw bytes
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawSubsection {
+    /// Subsection ID
+    pub id: u8,
+    /// Contents of the subsection (base64)
+    #[serde(with = "crate::serialize::base64")]
+    pub data: Cow<'static, [u8]>,
+}
+
+/// Asset referenced from an app description
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Asset {
+    /// Path the description uses to refer to the asset
+    pub path: String,
+    /// File to read the asset from (defaults to `path`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+}
@@ -8,34 +8,154 @@
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
 use alloc::{string::String, vec::Vec};
-use core::mem;
 
-use parity_wasm::elements::{self, Serialize};
+use crate::{
+    daku::{Daku, Localized, Read as _, SpecVersion, Write as _},
+    locale::Locale,
+    name::{Name, Read as _, Write as _},
+    parse::{Reader, Writer},
+    producers::{Producer, Read as _, Write as _},
+    section::SectionKind,
+    wasm::{Import, Read as _, Write as _},
+    Diagnostic, DiagnosticKind, Error, ErrorKind, Placement, Result, Section,
+    SectionId,
+};
 
-use crate::{section::SectionKind, Error, Result, Section};
+/// Magic number and version at the start of every WebAssembly module
+pub(crate) const HEADER: [u8; 8] = *b"\0asm\x01\0\0\0";
+
+/// A section of a WebAssembly module, kept exactly as it was encoded.
+#[derive(Debug)]
+struct RawSection {
+    /// Section ID (`0` for custom sections)
+    id: u8,
+    /// Name of the section, if a custom section
+    name: Option<String>,
+    /// The entire encoded section, including the ID and size
+    bytes: Vec<u8>,
+    /// Offset of the payload within `bytes` (after the name, if custom)
+    payload: usize,
+}
+
+impl RawSection {
+    /// Encode a new custom section.
+    fn custom(name: &str, payload: &[u8]) -> Result<Self> {
+        let mut contents = Vec::new();
+        let mut writer = Writer::new(&mut contents);
+
+        writer.name(name);
+
+        let name_len = contents.len();
+        let size = (name_len + payload.len())
+            .try_into()
+            .map_err(|_| Error::new(ErrorKind::Overflow, 0).in_section(name))?;
+        let mut bytes = Vec::new();
+        let mut writer = Writer::new(&mut bytes);
+
+        writer.u8(0);
+        writer.integer(size);
+        writer.bytes(contents);
+        writer.bytes(payload);
+
+        Ok(Self {
+            id: 0,
+            name: Some(name.into()),
+            payload: bytes.len() - payload.len(),
+            bytes,
+        })
+    }
+
+    /// Get the name of the section, if custom.
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the payload of the section.
+    fn payload(&self) -> &[u8] {
+        &self.bytes[self.payload..]
+    }
+
+    /// Get the kind of the section, if custom.
+    fn kind(&self) -> Option<SectionKind> {
+        self.name().map(SectionKind::of)
+    }
+
+    /// Return `true` if one of the `name`, `producers` or `daku` sections.
+    fn is_known(&self) -> bool {
+        self.kind().is_some_and(|kind| kind != SectionKind::Unknown)
+    }
+}
 
 /// Represents WebAssembly module. Use new to build from buffer.
+///
+/// Only the section IDs and sizes are read when building a `Module`, so every
+/// non-custom section is preserved byte for byte (even ones using WebAssembly
+/// proposals that this crate doesn't know about).  Custom sections are only
+/// parsed when requested.
 #[derive(Debug)]
-pub struct Module(elements::Module);
+pub struct Module {
+    sections: Vec<RawSection>,
+}
 
 impl Module {
     /// Creates a Module from buffer.
+    ///
+    /// With the `zstd` feature enabled, zstd-compressed `.daku` files are
+    /// decompressed automatically, failing with [`ErrorKind::TooLarge`] if the
+    /// module is over 1 GiB.
     pub fn new(buf: &[u8]) -> Result<Self> {
-        Ok(Module(elements::Module::from_bytes(buf).map_err(Error)?))
+        #[cfg(feature = "zstd")]
+        if buf.starts_with(&crate::container::MAGIC) {
+            return Self::new(&crate::container::decompress(buf)?);
+        }
+
+        let mut reader = Reader::new(buf);
+        let mut sections = Vec::new();
+
+        if reader.bytes(HEADER.len()).ok() != Some(&HEADER[..]) {
+            return Err(Error::new(ErrorKind::InvalidHeader, 0));
+        }
+
+        while !reader.is_empty() {
+            let start = reader.offset();
+            let id = reader.u8()?;
+            let size = reader.length()?;
+            let mut contents = reader.reader(size)?;
+            let name = if id == 0 {
+                Some(contents.name()?.into_owned())
+            } else {
+                None
+            };
+
+            sections.push(RawSection {
+                id,
+                name,
+                bytes: buf[start..reader.offset()].to_vec(),
+                payload: contents.offset() - start,
+            });
+        }
+
+        Ok(Self { sections })
     }
 
     /// Returns an iterator over the module’s custom sections.
     ///
     /// [`Section`]s are always yielded as the `Any` variant (borrowed).  They
     /// can be parsed with [`Section::to()`].
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::SectionOrder`] error if the `name`, `producers`
+    /// and `daku` sections are out of order or repeated.  Use
+    /// [`Module::sections_lenient()`] to get the sections anyway.
     pub fn sections(&self) -> Result<impl Iterator<Item = Section<'_>>> {
-        const ERROR_MESSAGE: Error = Error::with_msg("Incorrect Section Order");
-
         let mut kind = SectionKind::Name;
-        let iter = self.0.custom_sections();
 
-        for section in self.0.custom_sections() {
-            match section.name() {
+        for (offset, section) in self.custom() {
+            let Some(name) = section.name() else {
+                continue;
+            };
+
+            match name {
                 "name" if kind <= SectionKind::Name => {
                     kind = SectionKind::Producers
                 }
@@ -45,25 +165,246 @@ impl Module {
                 "daku" if kind <= SectionKind::Daku => {
                     kind = SectionKind::Unknown
                 }
-                "name" | "producers" | "daku" => return Err(ERROR_MESSAGE),
+                "name" | "producers" | "daku" => {
+                    return Err(Error::new(ErrorKind::SectionOrder, offset)
+                        .in_section(name))
+                }
                 _ => {}
             }
         }
 
-        Ok(iter.map(|section| Section::Any {
-            name: section.name().into(),
-            data: section.payload().into(),
-        }))
+        Ok(self.any_sections())
+    }
+
+    /// Returns an iterator over the module’s custom sections, along with every
+    /// problem found with them.
+    ///
+    /// Unlike [`Module::sections()`], this never fails.  Misordered and
+    /// duplicate `name`, `producers` and `daku` sections, and ones that fail to
+    /// parse, are reported as [`Diagnostic`]s instead (in the order they appear
+    /// in the module).
+    pub fn sections_lenient(
+        &self,
+    ) -> (impl Iterator<Item = Section<'_>>, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let mut seen = Vec::new();
+
+        for (index, (offset, section)) in self.custom().enumerate() {
+            let (Some(name), Some(kind)) = (section.name(), section.kind())
+            else {
+                continue;
+            };
+            let diagnostic = |kind| Diagnostic {
+                kind,
+                name: name.into(),
+                index,
+                offset,
+            };
+            let result = match kind {
+                SectionKind::Name => {
+                    parse_raw(offset, section, |r| r.names().map(drop))
+                }
+                SectionKind::Producers => {
+                    parse_raw(offset, section, |r| r.producers().map(drop))
+                }
+                SectionKind::Daku => {
+                    parse_raw(offset, section, |r| r.daku().map(drop))
+                }
+                SectionKind::Unknown => continue,
+            };
+
+            if seen.contains(&kind) {
+                diagnostics.push(diagnostic(DiagnosticKind::Duplicate));
+            } else if seen.iter().any(|&seen| seen > kind) {
+                diagnostics.push(diagnostic(DiagnosticKind::Misordered));
+            }
+
+            if let Err(error) = result {
+                diagnostics.push(diagnostic(DiagnosticKind::Malformed(error)));
+            }
+
+            seen.push(kind);
+        }
+
+        (self.any_sections(), diagnostics)
     }
 
     /// Sets the payload associated with the given custom section, or adds a new
     /// custom section, as appropriate.
-    pub fn set_section(&mut self, mut section: Section<'_>) -> Option<()> {
+    ///
+    /// Existing sections are replaced where they are.  New `name`,
+    /// `producers` and `daku` sections are kept in that order, and are added
+    /// after the last non-custom section.  Other custom sections are added at
+    /// the end.
+    pub fn set_section(&mut self, mut section: Section<'_>) -> Result {
         let (name, data) = section.to_any()?;
 
-        self.0.set_custom_section(name, data.to_vec());
+        self.set_raw(RawSection::custom(name, data)?);
 
-        Some(())
+        Ok(())
+    }
+
+    /// Puts a custom section at `placement`, replacing any existing custom
+    /// sections with the same name.
+    ///
+    /// The `name`, `producers` and `daku` sections are always kept in that
+    /// order; if `placement` would put one of them out of order, they are
+    /// swapped around to fix it.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::MissingSection`] error if the section referred
+    /// to by `placement` doesn't exist (the module is left unchanged).
+    pub fn insert_section(
+        &mut self,
+        mut section: Section<'_>,
+        placement: Placement<'_>,
+    ) -> Result {
+        let (name, data) = section.to_any()?;
+        let raw = RawSection::custom(name, data)?;
+        let missing = || {
+            let error = Error::new(ErrorKind::MissingSection, 0);
+
+            match placement {
+                Placement::BeforeCustom(name)
+                | Placement::AfterCustom(name) => error.in_section(name),
+                _ => error,
+            }
+        };
+
+        if let Placement::BeforeCustom(anchor)
+        | Placement::AfterCustom(anchor) = placement
+        {
+            if anchor == name {
+                return Err(missing());
+            }
+        }
+
+        if self.position(placement).is_none() {
+            return Err(missing());
+        }
+
+        self.sections.retain(|section| section.name() != Some(name));
+
+        let index = self.position(placement).ok_or_else(missing)?;
+
+        self.sections.insert(index, raw);
+        self.sort_known();
+
+        Ok(())
+    }
+
+    /// Parse the `name` section, if it exists.
+    pub fn names(&self) -> Result<Option<Vec<Name<'_>>>> {
+        self.parse("name", |reader| reader.names())
+    }
+
+    /// Parse the `producers` section, if it exists.
+    pub fn producers(&self) -> Result<Option<Vec<Producer<'_>>>> {
+        self.parse("producers", |reader| reader.producers())
+    }
+
+    /// Parse the `daku` section, if it exists.
+    pub fn daku(&self) -> Result<Option<Daku<'_>>> {
+        self.parse("daku", |reader| reader.daku())
+    }
+
+    /// Pick the best app name for a user's `preferred` locales (most preferred
+    /// first).
+    ///
+    /// Localized names are chosen as in [`Daku::name()`].  If there aren't
+    /// any that fit, the module name from the `name` section is used instead
+    /// (reported as [`Locale::Any`]).
+    pub fn app_name(
+        &self,
+        preferred: &[Locale],
+    ) -> Result<Option<Localized<String>>> {
+        let daku = self.daku()?;
+
+        if let Some(Localized { locale, value }) =
+            daku.as_ref().and_then(|daku| daku.name(preferred))
+        {
+            return Ok(Some(Localized {
+                locale,
+                value: value.into(),
+            }));
+        }
+
+        let module =
+            self.names()?
+                .into_iter()
+                .flatten()
+                .find_map(|name| match name {
+                    Name::Module(name) => Some(name),
+                    _ => None,
+                });
+
+        Ok(module.map(|name| Localized {
+            locale: Locale::Any,
+            value: name.into_owned(),
+        }))
+    }
+
+    /// Parse the import section, if it exists.
+    pub fn imports(&self) -> Result<Option<Vec<Import<'_>>>> {
+        self.offsets()
+            .find(|(_, section)| section.id == u8::from(SectionId::Import))
+            .map(|(offset, section)| {
+                let mut reader = Reader::with_offset(
+                    section.payload(),
+                    offset + section.payload,
+                );
+                let imports = reader.imports()?;
+
+                reader.end()?;
+                Ok(imports)
+            })
+            .transpose()
+    }
+
+    /// Set the `name` section, replacing it if it already exists.
+    pub fn set_names(&mut self, names: &[Name<'_>]) -> Result {
+        self.encode("name", |writer| writer.names(names))
+    }
+
+    /// Set the `producers` section, replacing it if it already exists.
+    pub fn set_producers(&mut self, producers: &[Producer<'_>]) -> Result {
+        self.encode("producers", |writer| {
+            writer.producers(producers);
+            Ok(())
+        })
+    }
+
+    /// Set the `daku` section, replacing it if it already exists.
+    pub fn set_daku(&mut self, daku: &Daku<'_>) -> Result {
+        self.encode("daku", |writer| writer.daku(daku))
+    }
+
+    /// Rewrite the `daku` section in the [`SpecVersion::V1`] layout, if it
+    /// uses the [`SpecVersion::Legacy`] layout.
+    ///
+    /// Sections are only rewritten if they have a subsection `0` (see
+    /// [`SpecVersion::detect()`]), since otherwise the layout can't be known
+    /// for sure.
+    ///
+    /// Returns whether the section was rewritten.
+    pub fn migrate_daku(&mut self) -> Result<bool> {
+        let legacy = self.parse("daku", |reader| {
+            Ok(SpecVersion::detect(reader.rest()) == SpecVersion::Legacy)
+        })?;
+
+        if legacy != Some(true) {
+            return Ok(false);
+        }
+
+        let daku = self
+            .parse("daku", |reader| reader.daku_as(SpecVersion::Legacy))?
+            .map(Daku::into_owned);
+
+        if let Some(daku) = daku {
+            self.set_daku(&daku)?;
+        }
+
+        Ok(true)
     }
 
     /// Removes the given custom section, if it exists. Returns the removed
@@ -72,22 +413,520 @@ impl Module {
         &mut self,
         name: impl AsRef<str>,
     ) -> Option<Section<'static>> {
-        let mut section = self.0.clear_custom_section(&name)?;
-        let (mut name, mut data) = (String::new(), Vec::new());
-
-        mem::swap(&mut name, section.name_mut());
-        mem::swap(&mut data, section.payload_mut());
+        let index = self.index_of(name.as_ref())?;
+        let section = self.sections.remove(index);
 
         Some(Section::Any {
-            name: name.into(),
-            data: data.into(),
+            data: section.payload().to_vec().into(),
+            name: section.name?.into(),
         })
     }
 
     /// Write out module to a `Vec` of bytes.
     pub fn into_buffer(self) -> Result<Vec<u8>> {
-        let mut v = Vec::new();
-        self.0.serialize(&mut v).map_err(Error)?;
-        Ok(v)
+        Ok(self.to_buffer())
+    }
+
+    /// Write out module to a `Vec` of bytes, without consuming it.
+    pub(crate) fn to_buffer(&self) -> Vec<u8> {
+        let mut buffer = HEADER.to_vec();
+
+        for section in &self.sections {
+            buffer.extend(&section.bytes);
+        }
+
+        buffer
+    }
+
+    /// Get the index of the first custom section called `name`.
+    pub(crate) fn index_of(&self, name: &str) -> Option<usize> {
+        self.sections.iter().position(|s| s.name() == Some(name))
+    }
+
+    /// Put a custom section back at `index` (or at the end, if there are
+    /// fewer sections), without moving any other sections.
+    pub(crate) fn restore_section(
+        &mut self,
+        index: usize,
+        mut section: Section<'_>,
+    ) -> Result {
+        let (name, data) = section.to_any()?;
+        let index = index.min(self.sections.len());
+
+        self.sections.insert(index, RawSection::custom(name, data)?);
+
+        Ok(())
+    }
+
+    /// Parse the first custom section called `name` with `parse`.
+    ///
+    /// Errors are reported relative to the start of the module.
+    pub(crate) fn parse<'a, T>(
+        &'a self,
+        name: &str,
+        parse: impl FnOnce(&mut Reader<'a>) -> Result<T>,
+    ) -> Result<Option<T>> {
+        self.custom()
+            .find(|(_, section)| section.name() == Some(name))
+            .map(|(offset, section)| parse_raw(offset, section, parse))
+            .transpose()
+    }
+
+    /// Iterate over the sections, along with their offsets.
+    fn offsets(&self) -> impl Iterator<Item = (usize, &RawSection)> {
+        self.sections.iter().scan(HEADER.len(), |offset, section| {
+            let start = *offset;
+
+            *offset += section.bytes.len();
+
+            Some((start, section))
+        })
+    }
+
+    /// Iterate over the custom sections, along with their offsets.
+    fn custom(&self) -> impl Iterator<Item = (usize, &RawSection)> {
+        self.offsets().filter(|(_, section)| section.id == 0)
+    }
+
+    /// Iterate over the custom sections as `Any` [`Section`]s.
+    fn any_sections(&self) -> impl Iterator<Item = Section<'_>> {
+        self.sections.iter().filter_map(|section| {
+            Some(Section::Any {
+                name: section.name()?.into(),
+                data: section.payload().into(),
+            })
+        })
+    }
+
+    /// Encode custom section called `name` with `encode`, and set it.
+    fn encode(
+        &mut self,
+        name: &str,
+        encode: impl FnOnce(&mut Writer<'_>) -> Result,
+    ) -> Result {
+        let mut data = Vec::new();
+
+        encode(&mut Writer::new(&mut data)).map_err(|e| e.in_section(name))?;
+        self.set_raw(RawSection::custom(name, &data)?);
+
+        Ok(())
+    }
+
+    /// Replace the first custom section with the same name where it is, or add
+    /// a new one.
+    fn set_raw(&mut self, raw: RawSection) {
+        let existing =
+            self.sections.iter_mut().find(|s| s.name() == raw.name());
+
+        match existing {
+            Some(section) => *section = raw,
+            None if raw.is_known() => {
+                let index = self.known_position(raw.kind());
+
+                self.sections.insert(index, raw);
+                self.sort_known();
+            }
+            None => self.sections.push(raw),
+        }
+    }
+
+    /// Get the index that `placement` refers to, if it exists.
+    fn position(&self, placement: Placement<'_>) -> Option<usize> {
+        let sections = &self.sections;
+
+        Some(match placement {
+            Placement::First => 0,
+            Placement::Last => sections.len(),
+            Placement::Before(id) => {
+                sections.iter().position(|s| s.id == u8::from(id))?
+            }
+            Placement::After(id) => {
+                sections.iter().rposition(|s| s.id == u8::from(id))? + 1
+            }
+            Placement::BeforeCustom(name) => {
+                sections.iter().position(|s| s.name() == Some(name))?
+            }
+            Placement::AfterCustom(name) => {
+                sections.iter().position(|s| s.name() == Some(name))? + 1
+            }
+        })
+    }
+
+    /// Get the index to add a new known section of `kind` at.
+    ///
+    /// That is before the known sections that go after it, after the known
+    /// sections that go before it, or otherwise after the last non-custom
+    /// section.
+    fn known_position(&self, kind: Option<SectionKind>) -> usize {
+        let sections = &self.sections;
+
+        if let Some(index) = sections
+            .iter()
+            .position(|s| s.is_known() && s.kind() > kind)
+        {
+            return index;
+        }
+
+        if let Some(index) = sections
+            .iter()
+            .rposition(|s| s.is_known() && s.kind() < kind)
+        {
+            return index + 1;
+        }
+
+        sections
+            .iter()
+            .rposition(|s| s.id != 0)
+            .map_or(0, |index| index + 1)
+    }
+
+    /// Reorder the `name`, `producers` and `daku` sections amongst their
+    /// current positions, so that they are in the correct order.
+    fn sort_known(&mut self) {
+        let slots: Vec<usize> = (0..self.sections.len())
+            .filter(|&index| self.sections[index].is_known())
+            .collect();
+        let mut known: Vec<RawSection> = slots
+            .iter()
+            .rev()
+            .map(|&index| self.sections.remove(index))
+            .collect();
+
+        known.reverse();
+        known.sort_by_key(RawSection::kind);
+
+        for (index, section) in slots.into_iter().zip(known) {
+            self.sections.insert(index, section);
+        }
+    }
+}
+
+/// Parse custom `section`, which starts at `offset`, with `parse`.
+///
+/// Errors are reported relative to the start of the module.
+fn parse_raw<'a, T>(
+    offset: usize,
+    section: &'a RawSection,
+    parse: impl FnOnce(&mut Reader<'a>) -> Result<T>,
+) -> Result<T> {
+    let mut reader =
+        Reader::with_offset(section.payload(), offset + section.payload);
+    let result = parse(&mut reader).and_then(|value| {
+        reader.end()?;
+        Ok(value)
+    });
+
+    result.map_err(|e| e.in_section(section.name().unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        daku::{Category, Nucleide, Portal, PortalSet},
+        producers::{ProducerKind, VersionedSoftware},
+    };
+
+    /// Module with a type section, an unknown (future proposal) section, and a
+    /// code section encoded with a padded (non-minimal) size.
+    #[rustfmt::skip]
+    const MODULE: &[u8] = &[
+        // Header
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+        // Type section
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+        // Unknown section
+        0x2A, 0x03, 0xDE, 0xAD, 0x00,
+        // Custom section
+        0x00, 0x05, 0x04, b'm', b'e', b't', b'a',
+        // Code section
+        0x0A, 0x84, 0x80, 0x80, 0x80, 0x00, 0x01, 0x02, 0x00, 0x0B,
+    ];
+
+    #[test]
+    fn preserves_bytes() {
+        let module = Module::new(MODULE).unwrap();
+
+        assert_eq!(module.into_buffer().unwrap(), MODULE);
+    }
+
+    #[test]
+    fn set_and_clear() {
+        let mut module = Module::new(MODULE).unwrap();
+        let section = Section::Any {
+            name: "meta".into(),
+            data: b"data"[..].into(),
+        };
+
+        module.set_section(section).unwrap();
+
+        let section = module.sections().unwrap().next().unwrap();
+
+        assert_eq!(section.name(), "meta");
+
+        let buffer = module.into_buffer().unwrap();
+
+        assert_eq!(buffer[..19], MODULE[..19]);
+        assert_eq!(buffer[19..30], *b"\x00\x09\x04metadata");
+        assert_eq!(buffer[30..], MODULE[26..]);
+
+        let mut module = Module::new(&buffer).unwrap();
+        let Some(Section::Any { name, data }) = module.clear_section("meta")
+        else {
+            panic!("missing section");
+        };
+
+        assert_eq!(name, "meta");
+        assert_eq!(&data[..], b"data");
+        assert_eq!(
+            module.into_buffer().unwrap(),
+            [&MODULE[..19], &MODULE[26..]].concat()
+        );
+    }
+
+    #[test]
+    fn typed_sections() {
+        let mut module = Module::new(MODULE).unwrap();
+        let names = [Name::Module("app".into())];
+        let producers = [Producer {
+            kind: ProducerKind::Sdk,
+            list: vec![VersionedSoftware {
+                name: "daku".into(),
+                version: "1.0.0".into(),
+            }],
+        }];
+        let daku = Daku {
+            portals: PortalSet::from([Portal::Log]),
+            nucleide: Some(vec![Nucleide::Developer("Ardaku".into())]),
+        };
+
+        assert_eq!(module.names().unwrap(), None);
+        module.set_names(&names).unwrap();
+        module.set_producers(&producers).unwrap();
+        module.set_daku(&daku).unwrap();
+        assert_eq!(module.names().unwrap().unwrap(), names);
+        assert_eq!(module.producers().unwrap().unwrap(), producers);
+        assert_eq!(module.daku().unwrap().unwrap(), daku);
+
+        let buffer = module.into_buffer().unwrap();
+        let module = Module::new(&buffer).unwrap();
+
+        assert_eq!(module.names().unwrap().unwrap(), names);
+        assert_eq!(module.daku().unwrap().unwrap(), daku);
+        assert_eq!(
+            module.app_name(&[]).unwrap(),
+            Some(Localized {
+                locale: Locale::Any,
+                value: "app".into(),
+            }),
+        );
+    }
+
+    #[test]
+    fn typed_section_error() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        module
+            .set_section(Section::Any {
+                name: "daku".into(),
+                data: [0x01, 0x80, 0x80, 0x80, 0x80, 0x10][..].into(),
+            })
+            .unwrap();
+
+        let error = module.daku().unwrap_err();
+
+        assert_eq!(error.section(), Some("daku"));
+        assert_eq!(error.offset(), MODULE.len() + 8);
+    }
+
+    #[test]
+    fn migrate_daku() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        assert!(!module.migrate_daku().unwrap());
+        module
+            .set_section(Section::Any {
+                name: "daku".into(),
+                data: [
+                    0x00, 0x00, 0x04, 0x01, 0x00, 0x01, 0x41, 0x06, 0x01, 0x00,
+                ][..]
+                    .into(),
+            })
+            .unwrap();
+
+        let daku = module.daku().unwrap().unwrap().into_owned();
+
+        assert_eq!(
+            daku.nucleide,
+            Some(vec![
+                Nucleide::LocalizedNames([(Locale::Any, "A".into())].into()),
+                Nucleide::Developer("".into()),
+            ]),
+        );
+        assert!(module.migrate_daku().unwrap());
+        assert!(!module.migrate_daku().unwrap());
+        assert_eq!(module.daku().unwrap().unwrap(), daku);
+
+        let section = module.sections().unwrap().find(|s| s.name() == "daku");
+
+        assert_eq!(
+            section,
+            Some(Section::Any {
+                name: "daku".into(),
+                data: [
+                    0x00, 0x01, 0x04, 0x01, 0x00, 0x01, 0x41, 0x07, 0x01, 0x00
+                ][..]
+                    .into(),
+            }),
+        );
+
+        // Unrecognized categories are never guessed to be a legacy developer
+        let categories = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::Categories(vec![
+                Category::Unknown(0x0A),
+            ])]),
+        };
+
+        module.set_daku(&categories).unwrap();
+
+        assert!(!module.migrate_daku().unwrap());
+        assert_eq!(module.daku().unwrap().unwrap(), categories);
+    }
+
+    /// Get the IDs and names of each section in the module.
+    fn layout(module: &Module) -> Vec<(u8, Option<&str>)> {
+        module.sections.iter().map(|s| (s.id, s.name())).collect()
+    }
+
+    fn any(name: &str) -> Section<'_> {
+        Section::Any {
+            name: name.into(),
+            data: [0x00][..].into(),
+        }
+    }
+
+    #[test]
+    fn known_order() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        module.set_section(any("daku")).unwrap();
+        module.set_section(any("producers")).unwrap();
+        module.set_section(any("name")).unwrap();
+        module.set_section(any("extra")).unwrap();
+        assert_eq!(
+            layout(&module),
+            [
+                (0x01, None),
+                (0x2A, None),
+                (0x00, Some("meta")),
+                (0x0A, None),
+                (0x00, Some("name")),
+                (0x00, Some("producers")),
+                (0x00, Some("daku")),
+                (0x00, Some("extra")),
+            ],
+        );
+        assert!(module.sections().is_ok());
+
+        // Fix up modules that are already out of order
+        let mut module = Module::new(MODULE).unwrap();
+
+        module
+            .insert_section(any("daku"), Placement::First)
+            .unwrap();
+        module.insert_section(any("name"), Placement::Last).unwrap();
+        assert_eq!(layout(&module)[0], (0x00, Some("name")));
+        assert_eq!(layout(&module)[5], (0x00, Some("daku")));
+        assert!(module.sections().is_ok());
+    }
+
+    #[test]
+    fn placement() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        module
+            .insert_section(any("a"), Placement::Before(SectionId::Code))
+            .unwrap();
+        module
+            .insert_section(any("b"), Placement::AfterCustom("meta"))
+            .unwrap();
+        module
+            .insert_section(any("c"), Placement::After(SectionId::Type))
+            .unwrap();
+        module
+            .insert_section(any("meta"), Placement::BeforeCustom("c"))
+            .unwrap();
+        assert_eq!(
+            layout(&module),
+            [
+                (0x01, None),
+                (0x00, Some("meta")),
+                (0x00, Some("c")),
+                (0x2A, None),
+                (0x00, Some("b")),
+                (0x00, Some("a")),
+                (0x0A, None),
+            ],
+        );
+
+        let error = module
+            .insert_section(any("d"), Placement::After(SectionId::Data))
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingSection);
+
+        let error = module
+            .insert_section(any("a"), Placement::AfterCustom("a"))
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingSection);
+        assert_eq!(error.section(), Some("a"));
+        assert_eq!(layout(&module).len(), 7);
+    }
+
+    #[test]
+    fn lenient() {
+        let mut module = Module::new(MODULE).unwrap();
+
+        for (name, data) in [
+            ("daku", &[0x01, 0x80, 0x80, 0x80, 0x80, 0x10][..]),
+            ("name", &[]),
+            ("producers", &[0x00]),
+            ("producers", &[0x00]),
+        ] {
+            module
+                .sections
+                .push(RawSection::custom(name, data).unwrap());
+        }
+
+        let error = module.sections().err().unwrap();
+
+        assert_eq!(error.kind(), ErrorKind::SectionOrder);
+        assert_eq!(error.section(), Some("name"));
+        assert_eq!(error.offset(), MODULE.len() + 13);
+
+        let (sections, diagnostics) = module.sections_lenient();
+
+        assert_eq!(sections.count(), 5);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (&d.kind, &d.name[..], d.index))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    &DiagnosticKind::Malformed(module.daku().unwrap_err()),
+                    "daku",
+                    1
+                ),
+                (&DiagnosticKind::Misordered, "name", 2),
+                (&DiagnosticKind::Misordered, "producers", 3),
+                (&DiagnosticKind::Duplicate, "producers", 4),
+            ],
+        );
+        assert_eq!(diagnostics[0].offset, MODULE.len());
     }
 }
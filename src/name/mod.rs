@@ -15,4 +15,5 @@ mod read;
 mod section;
 mod write;
 
+pub(crate) use self::section::owned_map;
 pub use self::{read::Read, section::Name, write::Write};
@@ -9,41 +9,63 @@
 
 use alloc::vec::Vec;
 
-use crate::{name::Name, parse::Reader, seal::Seal, wasm::Read as _};
+use crate::{
+    name::Name, parse::Reader, seal::Seal, wasm::Read as _, Error, ErrorKind,
+    Result,
+};
 
 /// Name section reader.
 pub trait Read<'a>: Seal {
     /// Parse standard WebAssembly name custom section.
-    fn names(&mut self) -> Option<Vec<Name<'a>>>;
+    fn names(&mut self) -> Result<Vec<Name<'a>>>;
 }
 
 impl<'a> Read<'a> for Reader<'a> {
-    fn names(&mut self) -> Option<Vec<Name<'a>>> {
+    fn names(&mut self) -> Result<Vec<Name<'a>>> {
         let mut names = Vec::new();
-        let mut subsection_min = 0;
+        let mut previous = None;
 
-        while self.end().is_none() {
+        while !self.is_empty() {
+            let offset = self.offset();
             let (subsection, mut reader) = self.subsection()?;
 
             // Must be ordered correctly
-            (subsection >= subsection_min).then_some(())?;
-            names.push(match subsection {
-                0 => Name::Module(reader.name()?),
-                1 => Name::Function(reader.name_map()?),
-                2 => Name::Local(reader.indirect_name_map()?),
-                3 => Name::Label(reader.indirect_name_map()?),
-                4 => Name::Type(reader.name_map()?),
-                5 => Name::Table(reader.name_map()?),
-                6 => Name::Memory(reader.name_map()?),
-                7 => Name::Global(reader.name_map()?),
-                8 => Name::Element(reader.name_map()?),
-                9 => Name::Data(reader.name_map()?),
-                _ => return None,
-            });
-            reader.end()?;
-            subsection_min = subsection + 1;
+            if previous.is_some_and(|previous| subsection <= previous) {
+                return Err(Error::new(ErrorKind::SubsectionOrder, offset)
+                    .in_subsection(subsection));
+            }
+
+            names.push(
+                name(subsection, &mut reader)
+                    .map_err(|e| e.in_subsection(subsection))?,
+            );
+            previous = Some(subsection);
         }
 
-        Some(names)
+        Ok(names)
     }
 }
+
+/// Parse the contents of a name subsection (all of `reader`).
+fn name<'a>(subsection: u8, reader: &mut Reader<'a>) -> Result<Name<'a>> {
+    let name = match subsection {
+        0 => Name::Module(reader.name()?),
+        1 => Name::Function(reader.name_map()?),
+        2 => Name::Local(reader.indirect_name_map()?),
+        3 => Name::Label(reader.indirect_name_map()?),
+        4 => Name::Type(reader.name_map()?),
+        5 => Name::Table(reader.name_map()?),
+        6 => Name::Memory(reader.name_map()?),
+        7 => Name::Global(reader.name_map()?),
+        8 => Name::Element(reader.name_map()?),
+        9 => Name::Data(reader.name_map()?),
+        id => Name::Unknown {
+            id,
+            data: reader.rest().into(),
+        },
+    };
+
+    reader.end()?;
+
+    Ok(name)
+}
@@ -10,7 +10,8 @@
 use alloc::{borrow::Cow, collections::BTreeMap};
 
 /// Name subsection
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Name<'a> {
     /// Module Name
     Module(Cow<'a, str>),
@@ -32,4 +33,55 @@ pub enum Name<'a> {
     Element(BTreeMap<u32, Cow<'a, str>>),
     /// Ext: Data Names
     Data(BTreeMap<u32, Cow<'a, str>>),
+    /// Subsection this crate doesn't know about, kept as-is
+    Unknown {
+        /// Subsection ID
+        id: u8,
+        /// Raw contents of the subsection
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serialize::base64")
+        )]
+        data: Cow<'a, [u8]>,
+    },
+}
+
+impl Name<'_> {
+    /// Convert into a name subsection that owns all of its data.
+    pub fn into_owned(self) -> Name<'static> {
+        match self {
+            Self::Module(name) => Name::Module(name.into_owned().into()),
+            Self::Function(map) => Name::Function(owned_map(map)),
+            Self::Local(map) => Name::Local(owned_indirect_map(map)),
+            Self::Label(map) => Name::Label(owned_indirect_map(map)),
+            Self::Type(map) => Name::Type(owned_map(map)),
+            Self::Table(map) => Name::Table(owned_map(map)),
+            Self::Memory(map) => Name::Memory(owned_map(map)),
+            Self::Global(map) => Name::Global(owned_map(map)),
+            Self::Element(map) => Name::Element(owned_map(map)),
+            Self::Data(map) => Name::Data(owned_map(map)),
+            Self::Unknown { id, data } => Name::Unknown {
+                id,
+                data: data.into_owned().into(),
+            },
+        }
+    }
+}
+
+/// Convert a name map into one that owns all of its data.
+pub(crate) fn owned_map<K: Ord>(
+    map: BTreeMap<K, Cow<'_, str>>,
+) -> BTreeMap<K, Cow<'static, str>> {
+    map.into_iter()
+        .map(|(index, name)| (index, name.into_owned().into()))
+        .collect()
+}
+
+/// Convert an indirect name map into one that owns all of its data.
+fn owned_indirect_map(
+    map: BTreeMap<u32, BTreeMap<u32, Cow<'_, str>>>,
+) -> BTreeMap<u32, BTreeMap<u32, Cow<'static, str>>> {
+    map.into_iter()
+        .map(|(index, map)| (index, owned_map(map)))
+        .collect()
 }
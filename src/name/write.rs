@@ -7,17 +7,22 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use crate::{name::Name, parse::Writer, seal::Seal, wasm::Write as _};
+use alloc::vec::Vec;
+
+use crate::{
+    name::Name, parse::Writer, seal::Seal, wasm::Write as _, Error, ErrorKind,
+    Result,
+};
 
 /// Name section writer
 pub trait Write<'a>: Seal {
     /// Write out standard WebAssembly name custom section.
-    fn names(&mut self, names: &[Name<'_>]) -> Option<()>;
+    fn names(&mut self, names: &[Name<'_>]) -> Result;
 }
 
 impl<'a> Write<'a> for Writer<'a> {
-    fn names(&mut self, names: &[Name<'_>]) -> Option<()> {
-        let mut subsection_min = 0;
+    fn names(&mut self, names: &[Name<'_>]) -> Result {
+        let mut previous = None;
 
         for name in names {
             let subsection = match name {
@@ -31,27 +36,40 @@ impl<'a> Write<'a> for Writer<'a> {
                 Name::Global(_) => 7,
                 Name::Element(_) => 8,
                 Name::Data(_) => 9,
+                Name::Unknown { id, .. } => *id,
             };
 
             // Must be ordered correctly
-            (subsection >= subsection_min).then_some(())?;
-            subsection_min = subsection + 1;
-            self.u8(subsection);
+            if previous.is_some_and(|previous| subsection <= previous) {
+                return Err(Error::new(
+                    ErrorKind::SubsectionOrder,
+                    self.offset(),
+                )
+                .in_subsection(subsection));
+            }
+
+            previous = Some(subsection);
+
+            let mut buffer = Vec::new();
+            let mut writer = Writer::new(&mut buffer);
 
             match name {
-                Name::Module(data) => self.name(data),
-                Name::Function(data) => self.name_map(data),
-                Name::Local(data) => self.indirect_name_map(data),
-                Name::Label(data) => self.indirect_name_map(data),
-                Name::Type(data) => self.name_map(data),
-                Name::Table(data) => self.name_map(data),
-                Name::Memory(data) => self.name_map(data),
-                Name::Global(data) => self.name_map(data),
-                Name::Element(data) => self.name_map(data),
-                Name::Data(data) => self.name_map(data),
+                Name::Module(data) => writer.name(data),
+                Name::Function(data) => writer.name_map(data),
+                Name::Local(data) => writer.indirect_name_map(data),
+                Name::Label(data) => writer.indirect_name_map(data),
+                Name::Type(data) => writer.name_map(data),
+                Name::Table(data) => writer.name_map(data),
+                Name::Memory(data) => writer.name_map(data),
+                Name::Global(data) => writer.name_map(data),
+                Name::Element(data) => writer.name_map(data),
+                Name::Data(data) => writer.name_map(data),
+                Name::Unknown { data, .. } => writer.bytes(data),
             };
+
+            self.subsection(subsection, &buffer);
         }
 
-        Some(())
+        Ok(())
     }
 }
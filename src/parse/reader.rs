@@ -7,92 +7,149 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use core::str;
+use core::{mem::size_of, str};
+
+use crate::{Error, ErrorKind, Result};
 
 /// Reads from a buffer.
-#[derive(Debug)]
-pub struct Reader<'a>(&'a [u8]);
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
 
 impl<'a> Reader<'a> {
     /// Create a new `Reader` on the provided `buffer`.
     pub fn new(buffer: &'a [u8]) -> Self {
-        Self(buffer)
+        Self::with_offset(buffer, 0)
+    }
+
+    /// Create a new `Reader` on the provided `buffer`, which starts at
+    /// `offset` within some larger buffer.
+    pub fn with_offset(buffer: &'a [u8], offset: usize) -> Self {
+        Self {
+            data: buffer,
+            offset,
+        }
+    }
+
+    /// Get the offset of the next byte to be read.
+    pub fn offset(&self) -> usize {
+        self.offset
     }
 
     /// Parse the next byte
-    pub fn u8(&mut self) -> Option<u8> {
-        const SIZE: usize = core::mem::size_of::<u8>();
+    pub fn u8(&mut self) -> Result<u8> {
+        const SIZE: usize = size_of::<u8>();
 
         let value = self.subslice(SIZE)?;
 
-        value.first().copied()
+        Ok(value[0])
     }
 
     /// Parse the next little-endian `u16`
-    pub fn u16(&mut self) -> Option<u16> {
-        const SIZE: usize = core::mem::size_of::<u16>();
+    pub fn u16(&mut self) -> Result<u16> {
+        const SIZE: usize = size_of::<u16>();
 
         let value = self.subslice(SIZE)?;
 
-        Some(u16::from_le_bytes(value.get(..SIZE)?.try_into().ok()?))
+        Ok(u16::from_le_bytes(
+            value.try_into().map_err(|_| self.truncated())?,
+        ))
     }
 
     /// Parse the next little-endian `u32`
-    pub fn u32(&mut self) -> Option<u32> {
-        const SIZE: usize = core::mem::size_of::<u32>();
+    pub fn u32(&mut self) -> Result<u32> {
+        const SIZE: usize = size_of::<u32>();
 
         let value = self.subslice(SIZE)?;
 
-        Some(u32::from_le_bytes(value.get(..SIZE)?.try_into().ok()?))
+        Ok(u32::from_le_bytes(
+            value.try_into().map_err(|_| self.truncated())?,
+        ))
     }
 
     /// Parse the next little-endian `u64`
-    pub fn u64(&mut self) -> Option<u64> {
-        const SIZE: usize = core::mem::size_of::<u64>();
+    pub fn u64(&mut self) -> Result<u64> {
+        const SIZE: usize = size_of::<u64>();
 
         let value = self.subslice(SIZE)?;
 
-        Some(u64::from_le_bytes(value.get(..SIZE)?.try_into().ok()?))
+        Ok(u64::from_le_bytes(
+            value.try_into().map_err(|_| self.truncated())?,
+        ))
     }
 
     /// Parse the next little-endian `u128`
-    pub fn u128(&mut self) -> Option<u128> {
-        const SIZE: usize = core::mem::size_of::<u128>();
+    pub fn u128(&mut self) -> Result<u128> {
+        const SIZE: usize = size_of::<u128>();
 
         let value = self.subslice(SIZE)?;
 
-        Some(u128::from_le_bytes(value.get(..SIZE)?.try_into().ok()?))
+        Ok(u128::from_le_bytes(
+            value.try_into().map_err(|_| self.truncated())?,
+        ))
     }
 
     /// Read a number of raw bytes.
-    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
-        self.subslice(len)?.get(..len)
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        self.subslice(len)
     }
 
     /// Parse a UTF-8 `String` of specified length.
-    pub fn str(&mut self, len: usize) -> Option<&'a str> {
-        str::from_utf8(self.bytes(len)?).ok()
+    pub fn str(&mut self, len: usize) -> Result<&'a str> {
+        let offset = self.offset;
+
+        str::from_utf8(self.bytes(len)?)
+            .map_err(|e| Error::new(ErrorKind::Utf8, offset + e.valid_up_to()))
     }
 
     /// Return a `Reader` that reads up to the specified length.
-    pub fn reader(&mut self, len: usize) -> Option<Self> {
-        Some(Self(self.subslice(len)?))
+    pub fn reader(&mut self, len: usize) -> Result<Self> {
+        let offset = self.offset;
+
+        Ok(Self::with_offset(self.subslice(len)?, offset))
+    }
+
+    /// Read all of the remaining bytes.
+    pub fn rest(&mut self) -> &'a [u8] {
+        let rest = self.data;
+
+        self.offset += rest.len();
+        self.data = &[];
+        rest
+    }
+
+    /// Return `true` if there are no more bytes to read.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Return `Ok(())` if end of buffer, otherwise a
+    /// [`ErrorKind::TrailingBytes`] error.
+    pub fn end(&self) -> Result<()> {
+        if !self.is_empty() {
+            return Err(Error::new(ErrorKind::TrailingBytes, self.offset));
+        }
+
+        Ok(())
     }
 
-    /// Return `Some(())` if end of buffer.
-    pub fn end(&self) -> Option<()> {
-        self.0.is_empty().then_some(())
+    /// Create a [`ErrorKind::Truncated`] error at the current offset.
+    fn truncated(&self) -> Error {
+        Error::new(ErrorKind::Truncated, self.offset)
     }
 
-    fn subslice(&mut self, size: usize) -> Option<&'a [u8]> {
-        if size > self.0.len() {
-            return None;
+    fn subslice(&mut self, size: usize) -> Result<&'a [u8]> {
+        if size > self.data.len() {
+            return Err(self.truncated());
         }
 
-        let (slice, data) = self.0.split_at(size);
+        let (slice, data) = self.data.split_at(size);
 
-        self.0 = data;
+        self.data = data;
+        self.offset += size;
 
-        Some(slice)
+        Ok(slice)
     }
 }
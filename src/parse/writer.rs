@@ -21,6 +21,11 @@ impl<'a> Writer<'a> {
         Self(buffer)
     }
 
+    /// Get the number of bytes in the buffer.
+    pub fn offset(&self) -> usize {
+        self.0.len()
+    }
+
     /// Write out `value` in ULEB128 encoding.
     pub fn uleb128<T: UInt>(&mut self, value: T) {
         let mut remaining = value;
@@ -91,7 +96,7 @@ mod tests {
             let mut reader = Reader::new(&writer.0[..]);
             let j = reader.integer().unwrap();
             assert_eq!(i, j);
-            assert!(reader.end().is_some());
+            assert!(reader.end().is_ok());
             writer.0.clear();
         }
         for i in (u64::from(u32::MAX) + 1)
@@ -100,7 +105,7 @@ mod tests {
             writer.uleb128(i);
             let mut reader = Reader::new(&writer.0[..]);
             let decoded = reader.integer();
-            assert!(decoded.is_none(), "{i} decoded is {decoded:?}");
+            assert!(decoded.is_err(), "{i} decoded is {decoded:?}");
             writer.0.clear();
         }
     }
@@ -0,0 +1,59 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use num_enum::{IntoPrimitive as Into, TryFromPrimitive as TryFrom};
+
+/// ID of a standard (non-custom) WebAssembly section
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Into, TryFrom)]
+pub enum SectionId {
+    /// Function signatures
+    Type = 0x01,
+    /// Imported functions, tables, memories and globals
+    Import = 0x02,
+    /// Type indices of each function in the code section
+    Function = 0x03,
+    /// Tables
+    Table = 0x04,
+    /// Memories
+    Memory = 0x05,
+    /// Globals
+    Global = 0x06,
+    /// Exported functions, tables, memories and globals
+    Export = 0x07,
+    /// Start function
+    Start = 0x08,
+    /// Element segments
+    Element = 0x09,
+    /// Function bodies
+    Code = 0x0A,
+    /// Data segments
+    Data = 0x0B,
+    /// Number of data segments
+    DataCount = 0x0C,
+    /// Exception handling tags
+    Tag = 0x0D,
+}
+
+/// Where to put a custom section within a module
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Placement<'a> {
+    /// Before all other sections
+    First,
+    /// After all other sections
+    Last,
+    /// Immediately before the first section with the given ID
+    Before(SectionId),
+    /// Immediately after the last section with the given ID
+    After(SectionId),
+    /// Immediately before the first custom section with the given name
+    BeforeCustom(&'a str),
+    /// Immediately after the first custom section with the given name
+    AfterCustom(&'a str),
+}
@@ -11,6 +11,7 @@ use alloc::{borrow::Cow, vec::Vec};
 
 /// Versioned software name
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VersionedSoftware<'a> {
     /// Name of the program/application/tool
     pub name: Cow<'a, str>,
@@ -19,21 +20,82 @@ pub struct VersionedSoftware<'a> {
 }
 
 /// Kind of producer
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
-pub enum ProducerKind {
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ProducerKind<'a> {
     /// Source language list
     Language,
     /// Individual tool list
     ProcessedBy,
     /// SDK list
     Sdk,
+    /// Field that isn't part of the conventions (such as a vendor extension)
+    Other(Cow<'a, str>),
 }
 
 /// Producer Field
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Producer<'a> {
     /// Kind of the list
-    pub kind: ProducerKind,
+    pub kind: ProducerKind<'a>,
     /// List of versioned names
     pub list: Vec<VersionedSoftware<'a>>,
 }
+
+impl<'a> ProducerKind<'a> {
+    /// Get the kind of producer with the field `name`.
+    pub fn from_name(name: impl Into<Cow<'a, str>>) -> Self {
+        let name = name.into();
+
+        match &*name {
+            "language" => Self::Language,
+            "processed-by" => Self::ProcessedBy,
+            "sdk" => Self::Sdk,
+            _ => Self::Other(name),
+        }
+    }
+
+    /// Get the field name (such as `"processed-by"`).
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Language => "language",
+            Self::ProcessedBy => "processed-by",
+            Self::Sdk => "sdk",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Convert into a producer kind that owns all of its data.
+    pub fn into_owned(self) -> ProducerKind<'static> {
+        match self {
+            Self::Language => ProducerKind::Language,
+            Self::ProcessedBy => ProducerKind::ProcessedBy,
+            Self::Sdk => ProducerKind::Sdk,
+            Self::Other(name) => ProducerKind::Other(name.into_owned().into()),
+        }
+    }
+}
+
+impl VersionedSoftware<'_> {
+    /// Convert into versioned software that owns all of its data.
+    pub fn into_owned(self) -> VersionedSoftware<'static> {
+        VersionedSoftware {
+            name: self.name.into_owned().into(),
+            version: self.version.into_owned().into(),
+        }
+    }
+}
+
+impl Producer<'_> {
+    /// Convert into a producer field that owns all of its data.
+    pub fn into_owned(self) -> Producer<'static> {
+        Producer {
+            kind: self.kind.into_owned(),
+            list: self
+                .list
+                .into_iter()
+                .map(VersionedSoftware::into_owned)
+                .collect(),
+        }
+    }
+}
@@ -14,34 +14,30 @@ use crate::{
     producers::{Producer, ProducerKind, VersionedSoftware},
     seal::Seal,
     wasm::Read as _,
+    Result,
 };
 
 /// Producers section reader.
 pub trait Read<'a>: Seal {
     /// Parse conventional WebAssembly producers custom section.
-    fn producers(&mut self) -> Option<Vec<Producer<'a>>>;
+    fn producers(&mut self) -> Result<Vec<Producer<'a>>>;
 }
 
 impl<'a> Read<'a> for Reader<'a> {
-    fn producers(&mut self) -> Option<Vec<Producer<'a>>> {
+    fn producers(&mut self) -> Result<Vec<Producer<'a>>> {
         (0..self.integer()?)
             .map(|_| {
-                let kind = match &*self.name()? {
-                    "language" => ProducerKind::Language,
-                    "processed-by" => ProducerKind::ProcessedBy,
-                    "sdk" => ProducerKind::Sdk,
-                    _ => return None,
-                };
+                let kind = ProducerKind::from_name(self.name()?);
                 let software = (0..self.integer()?)
                     .map(|_| {
-                        Some(VersionedSoftware {
+                        Ok(VersionedSoftware {
                             name: self.name()?,
                             version: self.name()?,
                         })
                     })
-                    .collect::<Option<_>>()?;
+                    .collect::<Result<_>>()?;
 
-                Some(Producer {
+                Ok(Producer {
                     kind,
                     list: software,
                 })
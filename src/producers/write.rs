@@ -7,12 +7,7 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use crate::{
-    parse::Writer,
-    producers::{Producer, ProducerKind},
-    seal::Seal,
-    wasm::Write as _,
-};
+use crate::{parse::Writer, producers::Producer, seal::Seal, wasm::Write as _};
 
 /// Producers section writer
 pub trait Write<'a>: Seal {
@@ -25,11 +20,7 @@ impl<'a> Write<'a> for Writer<'a> {
         self.integer(producers.len().try_into().unwrap_or(u32::MAX));
 
         for producer in producers {
-            self.name(match producer.kind {
-                ProducerKind::Language => "language",
-                ProducerKind::ProcessedBy => "processed-by",
-                ProducerKind::Sdk => "sdk",
-            });
+            self.name(producer.kind.as_str());
 
             self.integer(producer.list.len().try_into().unwrap_or(u32::MAX));
 
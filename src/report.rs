@@ -0,0 +1,425 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    daku::{Daku, File, Nucleide},
+    locale::Locale,
+    name::Name,
+    producers::Producer,
+    ErrorKind, Module, Section,
+};
+
+/// Machine-readable report of every custom section in a module (`serde`
+/// feature)
+///
+/// The JSON layout is versioned by [`Report::VERSION`], which is bumped on any
+/// change that could break consumers (adding new fields doesn't count).
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "sections": [
+///     {
+///       "name": "daku",
+///       "size": 42,
+///       "sha256": "…",
+///       "kind": "daku",
+///       "contents": {
+///         "portals": ["Fetch"],
+///         "names": { "*": "Notes", "deDE": "Notizen" },
+///         "icons": [
+///           {
+///             "theme": "default",
+///             "size": 1234,
+///             "sha256": "…",
+///             "format": "qoi"
+///           }
+///         ]
+///       }
+///     },
+///     { "name": "foo", "size": 3, "sha256": "…", "kind": "unknown" }
+///   ],
+///   "diagnostics": []
+/// }
+/// ```
+///
+/// `kind` is one of `"name"`, `"producers"`, `"daku"`, `"unknown"` (not
+/// decoded), or `"malformed"` (with an `error` message instead of `contents`).
+/// Embedded files are summarized by `size`, `sha256` and `format` (`"qoi"`,
+/// `"png"`, `"jpeg"`, `"gif"`, `"webp"`, `"svg"`, or `null` if not
+/// recognized), and locales are written as `"enUS"`, with `"*"` for
+/// non-localized values.  Icons are listed in order (a theme can have more
+/// than one), and producers of the same kind are merged into one list.
+/// `name` and nucleide subsections that aren't recognized are summarized under
+/// `unknown`, keyed by subsection ID.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// Version of the report layout
+    pub version: u32,
+    /// Every custom section, in the order they appear in the module
+    pub sections: Vec<SectionReport>,
+    /// Problems found with the custom sections
+    pub diagnostics: Vec<String>,
+}
+
+/// Report for a single custom section
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SectionReport {
+    /// Name of the custom section
+    pub name: String,
+    /// Size of the section contents in bytes
+    pub size: usize,
+    /// Hex-encoded SHA-256 hash of the section contents
+    pub sha256: String,
+    /// How the section was decoded
+    pub kind: &'static str,
+    /// Decoded contents of the section
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contents: Option<Value>,
+    /// Why the section failed to decode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Report {
+    /// Current version of the report layout
+    pub const VERSION: u32 = 1;
+
+    /// Render the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        // Serializing plain data structures to a string can't fail
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+impl Module {
+    /// Create a machine-readable report of every custom section (`serde`
+    /// feature).
+    pub fn report(&self) -> Report {
+        let (sections, diagnostics) = self.sections_lenient();
+        let sections = sections.map(section).collect();
+        let diagnostics = diagnostics.iter().map(ToString::to_string).collect();
+
+        Report {
+            version: Report::VERSION,
+            sections,
+            diagnostics,
+        }
+    }
+}
+
+fn section(section: Section<'_>) -> SectionReport {
+    let name = section.name().to_string();
+    let data = match &section {
+        Section::Any { data, .. } => data.as_ref(),
+        _ => &[],
+    };
+    let (kind, contents, error) = match section.to() {
+        Ok(Section::Name(names)) => ("name", Some(self::names(names)), None),
+        Ok(Section::Producers(producers)) => {
+            ("producers", Some(self::producers(producers)), None)
+        }
+        Ok(Section::Daku(daku)) => ("daku", Some(self::daku(daku)), None),
+        Ok(Section::Any { .. }) => ("unknown", None, None),
+        Err(e) if e.kind() == ErrorKind::Unsupported => ("unknown", None, None),
+        Err(e) => ("malformed", None, Some(e.to_string())),
+    };
+
+    SectionReport {
+        name,
+        size: data.len(),
+        sha256: sha256(data),
+        kind,
+        contents,
+        error,
+    }
+}
+
+fn names(names: Vec<Name<'_>>) -> Value {
+    let mut object = Map::new();
+    let mut unknown = Map::new();
+
+    for name in names {
+        let (key, value) = match name {
+            Name::Module(name) => ("module", json!(name)),
+            Name::Function(map) => ("function", json!(map)),
+            Name::Local(map) => ("local", json!(map)),
+            Name::Label(map) => ("label", json!(map)),
+            Name::Type(map) => ("type", json!(map)),
+            Name::Table(map) => ("table", json!(map)),
+            Name::Memory(map) => ("memory", json!(map)),
+            Name::Global(map) => ("global", json!(map)),
+            Name::Element(map) => ("element", json!(map)),
+            Name::Data(map) => ("data", json!(map)),
+            Name::Unknown { id, data } => {
+                unknown.insert(id.to_string(), blob(&data));
+                continue;
+            }
+        };
+
+        object.insert(key.into(), value);
+    }
+
+    if !unknown.is_empty() {
+        object.insert("unknown".into(), unknown.into());
+    }
+
+    object.into()
+}
+
+fn producers(producers: Vec<Producer<'_>>) -> Value {
+    let mut object = Map::new();
+
+    for producer in producers {
+        let key = producer.kind.as_str().to_string();
+        let list = producer
+            .list
+            .into_iter()
+            .map(|sw| json!({ "name": sw.name, "version": sw.version }));
+
+        // Repeated fields are merged, rather than replacing each other
+        if let Some(Value::Array(array)) = object.get_mut(&key) {
+            array.extend(list);
+        } else {
+            object.insert(key, list.collect::<Vec<_>>().into());
+        }
+    }
+
+    object.into()
+}
+
+fn daku(daku: Daku<'_>) -> Value {
+    let mut object = Map::new();
+    let mut unknown = Map::new();
+
+    object.insert("portals".into(), json!(daku.portals));
+
+    for subsection in daku.nucleide.into_iter().flatten() {
+        let (key, value) = match subsection {
+            Nucleide::LocalizedNames(map) => ("names", localized(map)),
+            Nucleide::LocalizedDescriptions(map) => {
+                ("descriptions", localized(map))
+            }
+            Nucleide::ThemedIcons(icons) => {
+                let icons: Vec<Value> = icons
+                    .into_iter()
+                    .map(|File { path, data }| {
+                        let mut value = blob(&data);
+
+                        value["theme"] = json!(path);
+                        value
+                    })
+                    .collect();
+
+                ("icons", icons.into())
+            }
+            Nucleide::LocalizedAssets(assets) => {
+                let assets: Map<String, Value> = assets
+                    .into_iter()
+                    .map(|(locale, File { path, data })| {
+                        let mut value = blob(&data);
+
+                        value["path"] = json!(path);
+                        (locale.to_string(), value)
+                    })
+                    .collect();
+
+                ("assets", assets.into())
+            }
+            Nucleide::Tags(tags) => ("tags", json!(tags)),
+            Nucleide::Categories(categories) => {
+                ("categories", json!(categories))
+            }
+            Nucleide::Developer(developer) => ("developer", json!(developer)),
+            Nucleide::Unknown { id, data } => {
+                unknown.insert(id.to_string(), blob(&data));
+                continue;
+            }
+        };
+
+        object.insert(key.into(), value);
+    }
+
+    if !unknown.is_empty() {
+        object.insert("unknown".into(), unknown.into());
+    }
+
+    object.into()
+}
+
+/// Locale-keyed map of strings
+fn localized(map: BTreeMap<Locale, Cow<'_, str>>) -> Value {
+    map.into_iter()
+        .map(|(locale, text)| (locale.to_string(), json!(text)))
+        .collect::<Map<String, Value>>()
+        .into()
+}
+
+/// Summary of an embedded file
+fn blob(data: &[u8]) -> Value {
+    json!({
+        "size": data.len(),
+        "sha256": sha256(data),
+        "format": image_format(data),
+    })
+}
+
+/// Detect the image format of a file from its magic bytes.
+fn image_format(data: &[u8]) -> Option<&'static str> {
+    let svg = |data: &[u8]| {
+        let start = data.iter().position(|b| !b.is_ascii_whitespace());
+
+        start.is_some_and(|start| {
+            data[start..].starts_with(b"<svg")
+                || data[start..].starts_with(b"<?xml")
+        })
+    };
+
+    Some(match data {
+        [b'q', b'o', b'i', b'f', ..] => "qoi",
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => "png",
+        [0xFF, 0xD8, 0xFF, ..] => "jpeg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+            "webp"
+        }
+        _ if svg(data) => "svg",
+        _ => return None,
+    })
+}
+
+/// Hex-encoded SHA-256 hash
+fn sha256(data: &[u8]) -> String {
+    let mut hex = String::with_capacity(64);
+
+    for byte in Sha256::digest(data) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        daku::{Portal, PortalSet},
+        module::HEADER,
+        producers::{ProducerKind, VersionedSoftware},
+    };
+
+    #[test]
+    fn report() {
+        let mut module = Module::new(&HEADER).unwrap();
+        let en_us: Locale = "en-US".parse().unwrap();
+
+        module
+            .set_daku(&Daku {
+                portals: PortalSet::from([Portal::Fetch]),
+                nucleide: Some(vec![
+                    Nucleide::LocalizedNames(BTreeMap::from([(
+                        en_us,
+                        "App".into(),
+                    )])),
+                    Nucleide::ThemedIcons(vec![
+                        File {
+                            path: "default".into(),
+                            data: b"qoif"[..].into(),
+                        },
+                        File {
+                            path: "default".into(),
+                            data: b"GIF89a"[..].into(),
+                        },
+                    ]),
+                ]),
+            })
+            .unwrap();
+        module
+            .set_section(Section::Any {
+                name: "foo".into(),
+                data: b"abc"[..].into(),
+            })
+            .unwrap();
+        module
+            .set_section(Section::Any {
+                name: "producers".into(),
+                data: [9][..].into(),
+            })
+            .unwrap();
+
+        let report = serde_json::to_value(module.report()).unwrap();
+
+        assert_eq!(report["version"], 1);
+        assert_eq!(report["sections"][0]["kind"], "malformed");
+        assert_eq!(
+            report["sections"][1]["contents"],
+            json!({
+                "portals": ["Fetch"],
+                "names": { "enUS": "App" },
+                "icons": [
+                    {
+                        "theme": "default",
+                        "size": 4,
+                        "sha256": sha256(b"qoif"),
+                        "format": "qoi",
+                    },
+                    {
+                        "theme": "default",
+                        "size": 6,
+                        "sha256": sha256(b"GIF89a"),
+                        "format": "gif",
+                    },
+                ],
+            }),
+        );
+        assert_eq!(
+            report["sections"][2],
+            json!({
+                "name": "foo",
+                "size": 3,
+                "sha256": "ba7816bf8f01cfea414140de5dae2223\
+                           b00361a396177a9cb410ff61f20015ad",
+                "kind": "unknown",
+            }),
+        );
+        assert_eq!(report["diagnostics"].as_array().unwrap().len(), 1);
+
+        let producer = |name: &'static str| Producer {
+            kind: ProducerKind::Language,
+            list: vec![VersionedSoftware {
+                name: name.into(),
+                version: "1".into(),
+            }],
+        };
+
+        assert_eq!(
+            producers(vec![producer("Rust"), producer("C")]),
+            json!({
+                "language": [
+                    { "name": "Rust", "version": "1" },
+                    { "name": "C", "version": "1" },
+                ],
+            }),
+        );
+    }
+}
@@ -14,9 +14,10 @@ use crate::{
     name::{Name, Read as _, Write as _},
     parse::{Reader, Writer},
     producers::{Producer, Read as _, Write as _},
+    Error, ErrorKind, Result,
 };
 
-#[derive(Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
 #[repr(u8)]
 pub(crate) enum SectionKind {
     Name = 0,
@@ -25,8 +26,20 @@ pub(crate) enum SectionKind {
     Unknown,
 }
 
+impl SectionKind {
+    /// Get the kind of the custom section with the given name.
+    pub(crate) fn of(name: &str) -> Self {
+        match name {
+            "name" => Self::Name,
+            "producers" => Self::Producers,
+            "daku" => Self::Daku,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Custom section
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Section<'a> {
     /// The `name` section
     Name(Vec<Name<'a>>),
@@ -57,13 +70,13 @@ impl Section<'_> {
     }
 
     /// Convert section to `Any` variant, and return the `name` and `data`.
-    pub fn to_any(&mut self) -> Option<(&str, &[u8])> {
-        let (name, mut data) = (String::new(), Vec::new());
+    pub fn to_any(&mut self) -> Result<(&str, &[u8])> {
+        let (name, mut data) = (String::from(self.name()), Vec::new());
         let writer = &mut Writer::new(&mut data);
 
         match self {
             Self::Name(names) => {
-                writer.names(names)?;
+                writer.names(names).map_err(|e| e.in_section("name"))?;
 
                 *self = Self::Any {
                     name: name.into(),
@@ -81,7 +94,7 @@ impl Section<'_> {
                 self.to_any()
             }
             Self::Daku(daku) => {
-                writer.daku(daku)?;
+                writer.daku(daku).map_err(|e| e.in_section("daku"))?;
 
                 *self = Self::Any {
                     name: name.into(),
@@ -89,46 +102,475 @@ impl Section<'_> {
                 };
                 self.to_any()
             }
-            Self::Any { name, data } => Some((&name[..], &data[..])),
+            Self::Any { name, data } => Ok((&name[..], &data[..])),
         }
     }
 
-    /// Convert to non-Any variant if known, return `None` if can't.
+    /// Convert to non-Any variant if known.
+    ///
+    /// Works for both borrowed and owned data; the returned section borrows
+    /// from `self`, use [`Section::into_owned()`] to detach it.
+    ///
+    /// Converting back with [`Section::to_any()`] reproduces the original
+    /// bytes exactly, as long as all LEB128 integers in the section were
+    /// minimally encoded (padded integers are accepted, but are re-encoded
+    /// without padding), and a `daku` section doesn't use the
+    /// [`SpecVersion::Legacy`](crate::daku::SpecVersion::Legacy) layout (which
+    /// is rewritten in the current layout) or list portals out of order or
+    /// more than once (which are written sorted, without duplicates).
     ///
-    /// # Notes
-    /// Returns none if owned rather than borrowed, or if not the `Any` variant.
-    pub fn to(&self) -> Option<Self> {
+    /// # Errors
+    /// Returns an [`ErrorKind::Unsupported`] error if not the `Any` variant, or
+    /// if the section name is unknown.  Otherwise, returns an error if the
+    /// section fails to parse.
+    pub fn to(&self) -> Result<Section<'_>> {
         let Self::Any { name, data } = self else {
-            return None;
-        };
-        let Cow::Borrowed(data) = data else {
-            return None;
+            return Err(Error::new(ErrorKind::Unsupported, 0));
         };
         let mut reader = Reader::new(data);
+        let section = match &name[..] {
+            "name" => reader.names().map(Section::Name),
+            "producers" => reader.producers().map(Section::Producers),
+            "daku" => reader.daku().map(Section::Daku),
+            _ => {
+                return Err(
+                    Error::new(ErrorKind::Unsupported, 0).in_section(name)
+                )
+            }
+        };
 
-        Some(match &name[..] {
-            "name" => {
-                let names = reader.names()?;
-
-                reader.end()?;
+        section
+            .and_then(|section| reader.end().map(|()| section))
+            .map_err(|e| e.in_section(name))
+    }
 
-                Self::Name(names)
+    /// Convert into a section that owns all of its data.
+    pub fn into_owned(self) -> Section<'static> {
+        match self {
+            Self::Name(names) => {
+                Section::Name(names.into_iter().map(Name::into_owned).collect())
             }
-            "producers" => {
-                let producers = reader.producers()?;
+            Self::Producers(producers) => Section::Producers(
+                producers.into_iter().map(Producer::into_owned).collect(),
+            ),
+            Self::Daku(daku) => Section::Daku(daku.into_owned()),
+            Self::Any { name, data } => Section::Any {
+                name: name.into_owned().into(),
+                data: data.into_owned().into(),
+            },
+        }
+    }
+}
 
-                reader.end()?;
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, vec};
 
-                Self::Producers(producers)
-            }
-            "daku" => {
-                let daku = reader.daku()?;
+    use super::*;
+    use crate::{
+        daku::{Category, File, Nucleide, Portal, PortalSet, SpecVersion},
+        locale::Locale,
+        producers::{ProducerKind, VersionedSoftware},
+    };
 
-                reader.end()?;
+    /// Parse, and then re-encode, checking that the bytes are unchanged.
+    fn roundtrip(name: &str, data: &[u8]) -> Section<'static> {
+        let section = Section::Any {
+            name: name.into(),
+            data: data.into(),
+        };
+        let parsed = section.to().unwrap();
+        let mut encoded = parsed.clone();
 
-                Self::Daku(daku)
-            }
-            _ => return None,
-        })
+        assert_eq!(encoded.to_any().unwrap(), (name, data));
+
+        parsed.into_owned()
+    }
+
+    fn parse(name: &str, data: &[u8]) -> Result<()> {
+        Section::Any {
+            name: name.into(),
+            data: data.into(),
+        }
+        .to()
+        .map(|_| ())
+    }
+
+    #[test]
+    fn error_location() {
+        let error =
+            parse("daku", &[0x01, 0x80, 0x80, 0x80, 0x80, 0x10]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Leb128);
+        assert_eq!(error.offset(), 1);
+        assert_eq!(error.section(), Some("daku"));
+        assert_eq!(error.subsection(), None);
+
+        let error = parse("daku", &[0x00, 0x07, 0x02, 0x01, 0xFF]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Utf8);
+        assert_eq!(error.offset(), 4);
+        assert_eq!(error.section(), Some("daku"));
+        assert_eq!(error.subsection(), Some(7));
+
+        let error = parse("name", &[0x01, 0x01, 0x00, 0x00]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Truncated);
+        assert_eq!(error.offset(), 4);
+        assert_eq!(error.section(), Some("name"));
+
+        let error = parse("producers", &[0x00, 0x00]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::TrailingBytes);
+        assert_eq!(error.offset(), 1);
+    }
+
+    #[test]
+    fn roundtrip_name() {
+        let data = [
+            &[0x00, 0x04, 0x03][..],
+            b"app",
+            &[0x01, 0x0A, 0x02, 0x00, 0x04],
+            b"main",
+            &[0x02, 0x01],
+            b"f",
+            &[0x02, 0x06, 0x01, 0x00, 0x01, 0x00, 0x01],
+            b"x",
+            &[0x07, 0x04, 0x01, 0x01, 0x01],
+            b"g",
+            &[0x0B, 0x02, 0xBE, 0xEF],
+        ]
+        .concat();
+        let expected = vec![
+            Name::Module("app".into()),
+            Name::Function(BTreeMap::from([
+                (0, "main".into()),
+                (2, "f".into()),
+            ])),
+            Name::Local(BTreeMap::from([(
+                0,
+                BTreeMap::from([(0, "x".into())]),
+            )])),
+            Name::Global(BTreeMap::from([(1, "g".into())])),
+            Name::Unknown {
+                id: 11,
+                data: [0xBE, 0xEF][..].into(),
+            },
+        ];
+
+        assert_eq!(roundtrip("name", &data), Section::Name(expected));
+        assert_eq!(roundtrip("name", &[]), Section::Name(vec![]));
+    }
+
+    #[test]
+    fn roundtrip_producers() {
+        let data = [
+            &[0x03, 0x08][..],
+            b"language",
+            &[0x01, 0x04],
+            b"Rust",
+            &[0x06],
+            b"1.70.0",
+            &[0x0C],
+            b"processed-by",
+            &[0x02, 0x05],
+            b"rustc",
+            &[0x06],
+            b"1.70.0",
+            &[0x08],
+            b"wasm-opt",
+            &[0x00, 0x08],
+            b"x-vendor",
+            &[0x00],
+        ]
+        .concat();
+        let expected = vec![
+            Producer {
+                kind: ProducerKind::Language,
+                list: vec![VersionedSoftware {
+                    name: "Rust".into(),
+                    version: "1.70.0".into(),
+                }],
+            },
+            Producer {
+                kind: ProducerKind::ProcessedBy,
+                list: vec![
+                    VersionedSoftware {
+                        name: "rustc".into(),
+                        version: "1.70.0".into(),
+                    },
+                    VersionedSoftware {
+                        name: "wasm-opt".into(),
+                        version: "".into(),
+                    },
+                ],
+            },
+            Producer {
+                kind: ProducerKind::Other("x-vendor".into()),
+                list: vec![],
+            },
+        ];
+
+        assert_eq!(roundtrip("producers", &data), Section::Producers(expected));
+    }
+
+    #[test]
+    fn roundtrip_daku() {
+        let data = [
+            &[0x03, 0x00, 0x02, 0x07][..],
+            &[0x01, 0x09, 0x01, 0xE5, 0xEE, 0xD5, 0x53, 0x03],
+            b"App",
+            &[0x03, 0x0D, 0x01, 0x07],
+            b"default",
+            &[0x03, 0x01, 0x02, 0x03],
+            &[0x04, 0x09, 0x01, 0x00, 0x04],
+            b"a.md",
+            &[0x01, 0x09],
+            &[0x05, 0x0D, 0x01, 0x0B],
+            b"text editor",
+            &[0x06, 0x03, 0x02, 0x01, 0x03],
+            &[0x07, 0x07, 0x06],
+            b"Ardaku",
+        ]
+        .concat();
+        let expected = Daku {
+            portals: PortalSet::from([
+                Portal::Log,
+                Portal::Fetch,
+                Portal::Camera,
+            ]),
+            nucleide: Some(vec![
+                Nucleide::LocalizedNames(BTreeMap::from([(
+                    "en-US".parse().unwrap(),
+                    "App".into(),
+                )])),
+                Nucleide::ThemedIcons(vec![File {
+                    path: "default".into(),
+                    data: [1, 2, 3][..].into(),
+                }]),
+                Nucleide::LocalizedAssets(BTreeMap::from([(
+                    Locale::Any,
+                    File {
+                        path: "a.md".into(),
+                        data: [9][..].into(),
+                    },
+                )])),
+                Nucleide::Tags(vec!["text editor".into()]),
+                Nucleide::Categories(vec![Category::Office, Category::Coding]),
+                Nucleide::Developer("Ardaku".into()),
+            ]),
+        };
+
+        assert_eq!(roundtrip("daku", &data), Section::Daku(expected));
+        assert_eq!(
+            roundtrip("daku", &[0x00]),
+            Section::Daku(Daku {
+                portals: PortalSet::new(),
+                nucleide: None,
+            }),
+        );
+        assert_eq!(
+            roundtrip("daku", &[0x01, 0x14, 0x06, 0x02, 0x01, 0x63]),
+            Section::Daku(Daku {
+                portals: PortalSet::from([Portal::Unknown(0x14)]),
+                nucleide: Some(vec![Nucleide::Categories(vec![
+                    Category::Unknown(0x63)
+                ])]),
+            }),
+        );
+    }
+
+    #[test]
+    fn portal_order() {
+        let section = Section::Any {
+            name: "daku".into(),
+            data: [0x04, 0x10, 0x02, 0x10, 0x50][..].into(),
+        };
+        let mut parsed = section.to().unwrap();
+
+        assert_eq!(
+            parsed,
+            Section::Daku(Daku {
+                portals: PortalSet::from([
+                    Portal::Fetch,
+                    Portal::Timer,
+                    Portal::Unknown(0x50),
+                ]),
+                nucleide: None,
+            }),
+        );
+        assert_eq!(
+            parsed.to_any().unwrap(),
+            ("daku", &[0x03, 0x02, 0x10, 0x50][..]),
+        );
+    }
+
+    #[test]
+    fn unknown_subsection() {
+        let data = [0x00, 0x07, 0x02, 0x01, 0x41, 0x09, 0x02, 0xDE, 0xAD];
+        let expected = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![
+                Nucleide::Developer("A".into()),
+                Nucleide::Unknown {
+                    id: 9,
+                    data: [0xDE, 0xAD][..].into(),
+                },
+            ]),
+        };
+
+        assert_eq!(roundtrip("daku", &data), Section::Daku(expected));
+
+        let error = parse("daku", &[0x00, 0x09, 0x00, 0x07, 0x00]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::SubsectionOrder);
+        assert_eq!(error.offset(), 3);
+        assert_eq!(error.subsection(), Some(7));
+    }
+
+    #[test]
+    fn legacy_layout() {
+        let legacy = [
+            &[0x00, 0x00, 0x05, 0x01, 0x00, 0x02][..],
+            b"Hi",
+            &[0x05, 0x02, 0x01, 0x03],
+            &[0x06, 0x02, 0x01, 0x41],
+        ]
+        .concat();
+        let current = [
+            &[0x00, 0x01, 0x05, 0x01, 0x00, 0x02][..],
+            b"Hi",
+            &[0x06, 0x02, 0x01, 0x03],
+            &[0x07, 0x02, 0x01, 0x41],
+        ]
+        .concat();
+        let expected = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![
+                Nucleide::LocalizedNames(BTreeMap::from([(
+                    Locale::Any,
+                    "Hi".into(),
+                )])),
+                Nucleide::Categories(vec![Category::Coding]),
+                Nucleide::Developer("A".into()),
+            ]),
+        };
+
+        assert_eq!(SpecVersion::detect(&legacy), SpecVersion::Legacy);
+        assert_eq!(SpecVersion::detect(&current), SpecVersion::V1);
+        assert_eq!(SpecVersion::detect(&[0x00]), SpecVersion::V1);
+        // Without subsection 0, the layout is never guessed
+        assert_eq!(
+            SpecVersion::detect(&[0x00, 0x04, 0x03, 0x01, 0x01, 0x41]),
+            SpecVersion::V1,
+        );
+
+        // Unrecognized categories, which are also a valid legacy developer
+        let mut written = Vec::new();
+        let categories = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::Categories(vec![
+                Category::Unknown(0x0A),
+            ])]),
+        };
+
+        Writer::new(&mut written).daku(&categories).unwrap();
+
+        assert_eq!(written, [0x00, 0x06, 0x02, 0x01, 0x0A]);
+        assert_eq!(SpecVersion::detect(&written), SpecVersion::V1);
+        assert_eq!(roundtrip("daku", &written), Section::Daku(categories));
+
+        let section = Section::Any {
+            name: "daku".into(),
+            data: legacy.into(),
+        };
+        let mut parsed = section.to().unwrap();
+
+        assert_eq!(parsed, Section::Daku(expected.clone()));
+        assert_eq!(parsed.to_any().unwrap(), ("daku", &current[..]));
+        assert_eq!(roundtrip("daku", &current), Section::Daku(expected));
+
+        let mut buffer = Vec::new();
+        let daku = Daku {
+            portals: PortalSet::new(),
+            nucleide: Some(vec![Nucleide::Unknown {
+                id: 0,
+                data: [][..].into(),
+            }]),
+        };
+
+        Writer::new(&mut buffer)
+            .daku_as(&daku, SpecVersion::Legacy)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn unordered_indices() {
+        let data = [
+            &[0x01, 0x07, 0x02, 0x02, 0x01][..],
+            b"a",
+            &[0x01, 0x01],
+            b"b",
+        ]
+        .concat();
+        let error = parse("name", &data).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::IndexOrder);
+        assert_eq!(error.offset(), 6);
+        assert_eq!(error.subsection(), Some(1));
+    }
+
+    #[test]
+    fn invalid_locale() {
+        let error = parse("daku", b"\x00\x00\x06\x01\x01\x03App").unwrap_err();
+
+        assert_eq!(
+            error.kind(),
+            ErrorKind::UnknownValue {
+                what: "locale",
+                value: 1,
+            },
+        );
+        assert_eq!(error.offset(), 4);
+        assert_eq!(error.subsection(), Some(0));
+    }
+
+    #[test]
+    fn recognized_unknown() {
+        let mut section = Section::Daku(Daku {
+            portals: PortalSet::from([Portal::Unknown(0x02)]),
+            nucleide: Some(vec![Nucleide::Categories(vec![
+                Category::Unknown(0x03),
+            ])]),
+        });
+        let error = section.to_any().unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Invalid);
+        assert_eq!(error.offset(), 2);
+        assert_eq!(error.subsection(), Some(6));
+
+        let Section::Daku(daku) = &mut section else {
+            unreachable!();
+        };
+
+        assert!(daku.portals.contains(Portal::Fetch));
+        daku.nucleide =
+            Some(vec![Nucleide::Categories(vec![Category::from(0x03)])]);
+
+        let (_, data) = section.to_any().unwrap();
+
+        assert_eq!(data, [0x01, 0x02, 0x06, 0x02, 0x01, 0x03]);
+    }
+
+    #[test]
+    fn owned() {
+        let section = Section::Any {
+            name: String::from("name").into(),
+            data: b"\x00\x04\x03app".to_vec().into(),
+        };
+        let parsed = section.to().unwrap().into_owned();
+
+        drop(section);
+        assert_eq!(parsed, Section::Name(vec![Name::Module("app".into())]));
     }
 }
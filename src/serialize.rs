@@ -0,0 +1,185 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+//! Helpers for serde support (`serde` feature).
+
+/// Serialize bytes as a base64 string.
+pub(crate) mod base64 {
+    use alloc::{borrow::Cow, string::String};
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        data: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub(crate) fn deserialize<'de, 'a, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Cow<'a, [u8]>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+
+        STANDARD
+            .decode(string)
+            .map(Cow::Owned)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serialize locales as readable strings (`"enUS"`), with `"*"` for
+/// non-localized, and parse `"en-US"` as well.
+mod locale {
+    use alloc::string::String;
+
+    use serde::{
+        de::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    use crate::locale::Locale;
+
+    impl Serialize for Locale {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Locale {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            String::deserialize(deserializer)?
+                .parse()
+                .map_err(D::Error::custom)
+        }
+    }
+}
+
+/// Serialize producer kinds as their field name (such as `"processed-by"`).
+mod producer_kind {
+    use alloc::string::String;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::producers::ProducerKind;
+
+    impl Serialize for ProducerKind<'_> {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ProducerKind<'_> {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            String::deserialize(deserializer).map(Self::from_name)
+        }
+    }
+}
+
+/// Serialize portal sets as a list of portals, ignoring duplicates when
+/// deserializing.
+mod portal_set {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::daku::{Portal, PortalSet};
+
+    impl Serialize for PortalSet {
+        fn serialize<S: Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PortalSet {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            Vec::<Portal>::deserialize(deserializer)
+                .map(|portals| portals.into_iter().collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, vec};
+
+    use serde_json::json;
+
+    use crate::{
+        daku::{Category, Daku, File, Nucleide, Portal, PortalSet},
+        locale::Locale,
+    };
+
+    #[test]
+    fn daku_json() {
+        let en_us = "en-US".parse().unwrap();
+        let daku = Daku {
+            portals: PortalSet::from([Portal::Fetch, Portal::Timer]),
+            nucleide: Some(vec![
+                Nucleide::LocalizedNames(BTreeMap::from([
+                    (Locale::Any, "App".into()),
+                    (en_us, "App (US)".into()),
+                ])),
+                Nucleide::LocalizedAssets(BTreeMap::from([(
+                    en_us,
+                    File {
+                        path: "a.qoi".into(),
+                        data: [1, 2, 3][..].into(),
+                    },
+                )])),
+                Nucleide::Categories(vec![Category::Coding]),
+            ]),
+        };
+        let value = serde_json::to_value(&daku).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "portals": ["Fetch", "Timer"],
+                "nucleide": [
+                    {"LocalizedNames": {"*": "App", "enUS": "App (US)"}},
+                    {"LocalizedAssets": {
+                        "enUS": {"path": "a.qoi", "data": "AQID"},
+                    }},
+                    {"Categories": ["Coding"]},
+                ],
+            }),
+        );
+        assert_eq!(serde_json::from_value::<Daku<'_>>(value).unwrap(), daku);
+
+        let value = json!({"LocalizedNames": {"en-US": "App (US)"}});
+
+        assert_eq!(
+            serde_json::from_value::<Nucleide<'_>>(value).unwrap(),
+            Nucleide::LocalizedNames(BTreeMap::from([(
+                en_us,
+                "App (US)".into()
+            )])),
+        );
+        assert!(serde_json::from_value::<Nucleide<'_>>(
+            json!({"LocalizedNames": {"english": "App"}})
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::vec::Vec;
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    module::HEADER,
+    parse::Writer,
+    wasm::{Read as _, Write as _},
+    Error, ErrorKind, Module, Result, Section,
+};
+
+/// Name of the custom section in a `.name` file that binds it to a module
+const BINDING: &str = "nucleide.binding";
+
+impl Module {
+    /// Strip the `name` section out of the module, and put it into a separate
+    /// `.name` file.
+    ///
+    /// Returns the stripped module and the contents of the `.name` file.  The
+    /// `.name` file is itself a WebAssembly module, containing only the `name`
+    /// section and a `nucleide.binding` custom section with the SHA-256 hash
+    /// of the stripped module, so that it can only be merged back into the
+    /// module it came from, followed by the index the `name` section was at.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::MissingSection`] error if there is no `name`
+    /// section.
+    pub fn split_names(mut self) -> Result<(Self, Vec<u8>)> {
+        let index = self.index_of("name");
+        let (Some(index), Some(names)) = (index, self.clear_section("name"))
+        else {
+            return Err(
+                Error::new(ErrorKind::MissingSection, 0).in_section("name")
+            );
+        };
+        let mut sidecar = Self::new(&HEADER)?;
+        let mut binding = self.hash().to_vec();
+
+        Writer::new(&mut binding).integer(index.try_into().unwrap_or(u32::MAX));
+        sidecar.set_section(Section::Any {
+            name: BINDING.into(),
+            data: binding.into(),
+        })?;
+        sidecar.set_section(names)?;
+
+        Ok((self, sidecar.to_buffer()))
+    }
+
+    /// Merge the `name` section from a `.name` file created by
+    /// [`Module::split_names()`] back into the module, at the index it was
+    /// split from.
+    ///
+    /// # Errors
+    /// Returns an [`ErrorKind::Mismatch`] error if the `.name` file was split
+    /// from a different module, and an [`ErrorKind::MissingSection`] error if
+    /// it isn't a valid `.name` file.
+    pub fn merge_names(&mut self, sidecar: &[u8]) -> Result {
+        let mut sidecar = Self::new(sidecar)?;
+        let missing =
+            |name| Error::new(ErrorKind::MissingSection, 0).in_section(name);
+        let (hash, index) = sidecar
+            .parse(BINDING, |reader| {
+                Ok((reader.bytes(32)?, reader.integer()?))
+            })?
+            .ok_or_else(|| missing(BINDING))?;
+
+        if hash != self.hash() {
+            return Err(Error::new(ErrorKind::Mismatch, 0).in_section(BINDING));
+        }
+
+        let names = sidecar
+            .clear_section("name")
+            .ok_or_else(|| missing("name"))?;
+
+        self.restore_section(index.try_into().unwrap_or(usize::MAX), names)
+    }
+
+    /// Get the SHA-256 hash of the encoded module.
+    fn hash(&self) -> [u8; 32] {
+        Sha256::digest(self.to_buffer()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{name::Name, Placement};
+
+    #[test]
+    fn split_and_merge() {
+        let mut module = Module::new(&HEADER).unwrap();
+        let names = vec![Name::Module("app".into())];
+
+        module.set_names(&names).unwrap();
+        module
+            .set_section(Section::Any {
+                name: "other".into(),
+                data: [1][..].into(),
+            })
+            .unwrap();
+
+        let original = module.to_buffer();
+        let (mut stripped, sidecar) = module.split_names().unwrap();
+
+        assert_eq!(stripped.names().unwrap(), None);
+
+        let mut other = Module::new(&HEADER).unwrap();
+        let error = other.merge_names(&sidecar).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Mismatch);
+
+        stripped.merge_names(&sidecar).unwrap();
+        assert_eq!(stripped.names().unwrap().unwrap(), names);
+        assert_eq!(stripped.to_buffer(), original);
+
+        let error =
+            stripped.split_names().unwrap().0.split_names().unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingSection);
+    }
+
+    #[test]
+    fn merge_in_place() {
+        let mut module = Module::new(&HEADER).unwrap();
+        let names = vec![Name::Module("app".into())];
+
+        module
+            .set_section(Section::Any {
+                name: "other".into(),
+                data: [1][..].into(),
+            })
+            .unwrap();
+        module
+            .insert_section(
+                Section::Name(names.clone()),
+                Placement::AfterCustom("other"),
+            )
+            .unwrap();
+
+        let original = module.to_buffer();
+        let (mut stripped, sidecar) = module.split_names().unwrap();
+
+        stripped.merge_names(&sidecar).unwrap();
+        assert_eq!(stripped.to_buffer(), original);
+
+        stripped.set_names(&names).unwrap();
+        assert_eq!(stripped.to_buffer(), original);
+    }
+}
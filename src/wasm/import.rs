@@ -0,0 +1,47 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use alloc::borrow::Cow;
+
+/// An entry in the import section
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Import<'a> {
+    /// Name of the module the item is imported from
+    pub module: Cow<'a, str>,
+    /// Name of the imported item
+    pub name: Cow<'a, str>,
+    /// What kind of item is imported
+    pub kind: ImportKind,
+}
+
+impl Import<'_> {
+    /// Convert into an import that owns all of its data.
+    pub fn into_owned(self) -> Import<'static> {
+        Import {
+            module: self.module.into_owned().into(),
+            name: self.name.into_owned().into(),
+            kind: self.kind,
+        }
+    }
+}
+
+/// Kind of an imported item
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImportKind {
+    /// Function (with its type index)
+    Function(u32),
+    /// Table
+    Table,
+    /// Memory
+    Memory,
+    /// Global
+    Global,
+    /// Exception handling tag (with its type index)
+    Tag(u32),
+}
@@ -9,7 +9,12 @@
 //
 //! Parsing extensions for WebAssembly-specific format primitives.
 
+mod import;
 mod read;
 mod write;
 
-pub use self::{read::Read, write::Write};
+pub use self::{
+    import::{Import, ImportKind},
+    read::Read,
+    write::Write,
+};
@@ -9,33 +9,52 @@
 
 use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
 
-use crate::{parse::Reader, seal::Seal};
+use crate::{
+    parse::Reader,
+    seal::Seal,
+    wasm::{Import, ImportKind},
+    Error, ErrorKind, Result,
+};
 
 /// WebAssembly primitive reader methods
 pub trait Read<'a>: Seal {
     /// Parse the next ULEB128-encoded 32-bit unsigned integer.
-    fn integer(&mut self) -> Option<u32>;
+    fn integer(&mut self) -> Result<u32>;
+
+    /// Parse the next ULEB128-encoded 32-bit length.
+    fn length(&mut self) -> Result<usize>;
+
+    /// Parse the next ULEB128-encoded 32-bit map index, which must be greater
+    /// than the `previous` index.
+    fn index(&mut self, previous: Option<u32>) -> Result<u32>;
 
     /// Parse a WebAssembly "Name".
-    fn name(&mut self) -> Option<Cow<'a, str>>;
+    fn name(&mut self) -> Result<Cow<'a, str>>;
 
     /// Parse a WebAssembly "Vector" of "Name"s.
-    fn name_vector(&mut self) -> Option<Vec<Cow<'a, str>>>;
+    fn name_vector(&mut self) -> Result<Vec<Cow<'a, str>>>;
 
     /// Parse a WebAssembly "Name Map".
-    fn name_map(&mut self) -> Option<BTreeMap<u32, Cow<'a, str>>>;
+    fn name_map(&mut self) -> Result<BTreeMap<u32, Cow<'a, str>>>;
 
     /// Parse a WebAssembly "Indirect Name Map".
     fn indirect_name_map(
         &mut self,
-    ) -> Option<BTreeMap<u32, BTreeMap<u32, Cow<'a, str>>>>;
+    ) -> Result<BTreeMap<u32, BTreeMap<u32, Cow<'a, str>>>>;
 
     /// Parse a WebAssembly "Subsection"
-    fn subsection(&mut self) -> Option<(u8, Reader<'a>)>;
+    fn subsection(&mut self) -> Result<(u8, Reader<'a>)>;
+
+    /// Parse an import section.
+    fn imports(&mut self) -> Result<Vec<Import<'a>>>;
+
+    /// Parse an entry in the import section.
+    fn import(&mut self) -> Result<Import<'a>>;
 }
 
 impl<'a> Read<'a> for Reader<'a> {
-    fn integer(&mut self) -> Option<u32> {
+    fn integer(&mut self) -> Result<u32> {
+        let offset = self.offset();
         let mut value = 0;
         let mut shift = 0;
 
@@ -49,51 +68,160 @@ impl<'a> Read<'a> for Reader<'a> {
             let fits_u32 = more || byte < 16;
 
             if byte & 0x80 == 0 && fits_u32 {
-                return Some(value);
+                return Ok(value);
             }
 
             more
         } {}
 
-        None
+        Err(Error::new(ErrorKind::Leb128, offset))
     }
 
-    fn name(&mut self) -> Option<Cow<'a, str>> {
-        let len = self.integer()?.try_into().ok()?;
+    fn length(&mut self) -> Result<usize> {
+        let offset = self.offset();
+
+        self.integer()?
+            .try_into()
+            .map_err(|_| Error::new(ErrorKind::Truncated, offset))
+    }
+
+    fn index(&mut self, previous: Option<u32>) -> Result<u32> {
+        let offset = self.offset();
+        let index = self.integer()?;
+
+        if previous.is_some_and(|previous| index <= previous) {
+            return Err(Error::new(ErrorKind::IndexOrder, offset));
+        }
+
+        Ok(index)
+    }
+
+    fn name(&mut self) -> Result<Cow<'a, str>> {
+        let len = self.length()?;
 
         self.str(len).map(From::from)
     }
 
-    fn name_vector(&mut self) -> Option<Vec<Cow<'a, str>>> {
+    fn name_vector(&mut self) -> Result<Vec<Cow<'a, str>>> {
         (0..self.integer()?).map(|_| self.name()).collect()
     }
 
-    fn name_map(&mut self) -> Option<BTreeMap<u32, Cow<'a, str>>> {
+    fn name_map(&mut self) -> Result<BTreeMap<u32, Cow<'a, str>>> {
         let mut name_map = BTreeMap::new();
 
         for _ in 0..self.integer()? {
-            name_map.insert(self.integer()?, self.name()?);
+            let index = self.index(name_map.keys().next_back().copied())?;
+
+            name_map.insert(index, self.name()?);
         }
 
-        Some(name_map)
+        Ok(name_map)
     }
 
     fn indirect_name_map(
         &mut self,
-    ) -> Option<BTreeMap<u32, BTreeMap<u32, Cow<'a, str>>>> {
+    ) -> Result<BTreeMap<u32, BTreeMap<u32, Cow<'a, str>>>> {
         let mut indirect_name_map = BTreeMap::new();
 
         for _ in 0..self.integer()? {
-            indirect_name_map.insert(self.integer()?, self.name_map()?);
+            let index =
+                self.index(indirect_name_map.keys().next_back().copied())?;
+
+            indirect_name_map.insert(index, self.name_map()?);
         }
 
-        Some(indirect_name_map)
+        Ok(indirect_name_map)
     }
 
-    fn subsection(&mut self) -> Option<(u8, Reader<'a>)> {
+    fn subsection(&mut self) -> Result<(u8, Reader<'a>)> {
         let subsection = self.u8()?;
-        let len = self.integer()?.try_into().ok()?;
+        let len = self.length()?;
+
+        self.reader(len)
+            .map(|reader| (subsection, reader))
+            .map_err(|e| e.in_subsection(subsection))
+    }
+
+    fn imports(&mut self) -> Result<Vec<Import<'a>>> {
+        (0..self.integer()?).map(|_| self.import()).collect()
+    }
+
+    fn import(&mut self) -> Result<Import<'a>> {
+        let module = self.name()?;
+        let name = self.name()?;
+        let offset = self.offset();
+        let kind = match self.u8()? {
+            0x00 => ImportKind::Function(self.integer()?),
+            0x01 => {
+                value_type(self)?;
+                limits(self)?;
+                ImportKind::Table
+            }
+            0x02 => {
+                limits(self)?;
+                ImportKind::Memory
+            }
+            0x03 => {
+                value_type(self)?;
+                self.u8()?;
+                ImportKind::Global
+            }
+            0x04 => {
+                self.u8()?;
+                ImportKind::Tag(self.integer()?)
+            }
+            kind => {
+                return Err(Error::new(
+                    ErrorKind::UnknownValue {
+                        what: "import kind",
+                        value: kind.into(),
+                    },
+                    offset,
+                ))
+            }
+        };
+
+        Ok(Import { module, name, kind })
+    }
+}
+
+/// Skip a LEB128 integer of up to 64 bits (signed or unsigned).
+fn skip_leb128(reader: &mut Reader<'_>) -> Result<()> {
+    let offset = reader.offset();
+
+    for _ in 0..10 {
+        if reader.u8()? & 0x80 == 0 {
+            return Ok(());
+        }
+    }
+
+    Err(Error::new(ErrorKind::Leb128, offset))
+}
+
+/// Skip the limits of a table or memory.
+fn limits(reader: &mut Reader<'_>) -> Result<()> {
+    let flags = reader.u8()?;
+
+    // Minimum, and then maximum if it has one
+    skip_leb128(reader)?;
+
+    if flags & 0x01 != 0 {
+        skip_leb128(reader)?;
+    }
+
+    // Custom page size
+    if flags & 0x08 != 0 {
+        reader.integer()?;
+    }
+
+    Ok(())
+}
 
-        Some((subsection, self.reader(len)?))
+/// Skip a value type (or a table's reference type).
+fn value_type(reader: &mut Reader<'_>) -> Result<()> {
+    match reader.u8()? {
+        // Reference type with a heap type
+        0x63 | 0x64 => skip_leb128(reader),
+        _ => Ok(()),
     }
 }
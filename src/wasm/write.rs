@@ -11,7 +11,7 @@ use alloc::{borrow::Cow, collections::BTreeMap};
 
 use crate::{parse::Writer, seal::Seal};
 
-/// WebAssembly primitive reader methods
+/// WebAssembly primitive writer methods
 pub trait Write<'a>: Seal {
     /// Encode the next ULEB128-encoded 32-bit unsigned integer.
     fn integer(&mut self, int: u32);
@@ -31,7 +31,7 @@ pub trait Write<'a>: Seal {
         indirect_name_map: &BTreeMap<u32, BTreeMap<u32, Cow<'_, str>>>,
     );
 
-    /// Encode a WebAssembly "Subsection"
+    /// Encode a WebAssembly "Subsection" (ID, size and then `data`)
     fn subsection(&mut self, subsection: u8, data: &[u8]);
 }
 
@@ -78,6 +78,7 @@ impl<'a> Write<'a> for Writer<'a> {
 
     fn subsection(&mut self, subsection: u8, data: &[u8]) {
         self.u8(subsection);
+        self.integer(data.len().try_into().unwrap_or(u32::MAX));
         self.bytes(data);
     }
 }
@@ -0,0 +1,201 @@
+// Copyright © 2022-2023 The Nucleide Contributors.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// - MIT License (https://mit-license.org/)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+#![cfg(feature = "cli")]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command, Output},
+};
+
+/// Module with no sections
+const EMPTY: &[u8] = &[0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+/// Create an empty directory for a test, with `EMPTY` written to
+/// `module.wasm`.
+fn setup(test: &str) -> PathBuf {
+    let dir =
+        env::temp_dir().join(format!("nucleide-cli-{}-{test}", process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("module.wasm"), EMPTY).unwrap();
+    dir
+}
+
+/// Run `nucleide` with `args`, in `dir`.
+fn nucleide(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nucleide"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn validate() {
+    let dir = setup("validate");
+    let output = nucleide(&dir, &["validate", "module.wasm"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("missing required `daku` section"));
+
+    let output = nucleide(&dir, &["set", "module.wasm", "--portal", "Fetch"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = nucleide(&dir, &["validate", "module.wasm"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(stdout(&output).starts_with('✓'));
+
+    let output = nucleide(&dir, &["validate", "missing.wasm"]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("error: missing.wasm"));
+}
+
+#[test]
+fn set_and_get() {
+    let dir = setup("set_and_get");
+    let output = nucleide(
+        &dir,
+        &[
+            "set",
+            "module.wasm",
+            "--portal=Timer",
+            "--portal=Log",
+            "--tag=notes",
+            "--developer=Ardaku",
+            "--module=notes",
+            "--output=notes.daku",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(fs::read(dir.join("module.wasm")).unwrap(), EMPTY);
+
+    let get = |field| stdout(&nucleide(&dir, &["get", "notes.daku", field]));
+
+    assert_eq!(get("portals"), "Log\nTimer\n");
+    assert_eq!(get("tags"), "notes\n");
+    assert_eq!(get("developer"), "Ardaku\n");
+    assert_eq!(get("module"), "notes\n");
+
+    let output = nucleide(&dir, &["get", "notes.daku", "categories"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).is_empty());
+
+    let output = nucleide(&dir, &["set", "module.wasm", "--portal=Teleport"]);
+
+    assert!(!output.status.success());
+    assert_eq!(stderr(&output), "error: unknown portal `Teleport`\n");
+}
+
+#[test]
+fn infer_portals() {
+    let dir = setup("infer_portals");
+    #[rustfmt::skip]
+    let module = [
+        EMPTY,
+        // Type section
+        &[0x01, 0x04, 0x01, 0x60, 0x00, 0x00],
+        // Import section, with `a.b`.`c` and `daku`.`timer`
+        &[0x02, 0x16, 0x02],
+        &[0x03, b'a', b'.', b'b', 0x01, b'c', 0x00, 0x00],
+        &[0x04, b'd', b'a', b'k', b'u', 0x05, b't', b'i', b'm', b'e', b'r'],
+        &[0x00, 0x00],
+    ]
+    .concat();
+
+    fs::write(dir.join("imports.wasm"), module).unwrap();
+
+    let output = nucleide(&dir, &["set", "module.wasm", "--infer-portals"]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("no imports map to a portal"));
+
+    let output = nucleide(
+        &dir,
+        &[
+            "set",
+            "imports.wasm",
+            "--infer-portals",
+            "--portal-import=a.b.c=Fetch",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = nucleide(&dir, &["get", "imports.wasm", "portals"]);
+
+    assert_eq!(stdout(&output), "Fetch\nTimer\n");
+}
+
+#[test]
+fn inspect_and_extract() {
+    let dir = setup("inspect_and_extract");
+
+    fs::write(dir.join("icon.qoi"), b"qoif").unwrap();
+    fs::write(
+        dir.join("nucleide.toml"),
+        "portals = [\"Log\"]\n\n[icons]\ndefault = [\"icon.qoi\"]\n\n\
+         [assets]\n\
+         en-US = { path = \"a.qoi\", file = \"icon.qoi\" }\n\
+         de-DE = { path = \"a.qoi\", file = \"icon.qoi\" }\n",
+    )
+    .unwrap();
+
+    let output =
+        nucleide(&dir, &["set", "module.wasm", "--manifest=nucleide.toml"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = nucleide(&dir, &["inspect", "module.wasm"]);
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.contains("• Log"), "{text}");
+    assert!(text.contains("• default: 4 bytes"), "{text}");
+
+    let output = nucleide(&dir, &["get", "module.wasm", "assets"]);
+
+    assert_eq!(
+        stdout(&output),
+        "deDE\ta.qoi: 4 bytes\nenUS\ta.qoi: 4 bytes\n",
+    );
+
+    let output = nucleide(&dir, &["inspect", "--json", "module.wasm"]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with('{'));
+
+    let output = nucleide(
+        &dir,
+        &["extract-assets", "module.wasm", "out", "--manifest"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(dir.join("out/nucleide.toml").exists());
+
+    let output = nucleide(&dir, &["diff", "module.wasm", "module.wasm"]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).is_empty());
+}